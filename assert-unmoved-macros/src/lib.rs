@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+/*!
+Implementation detail of the [`assert-unmoved`] crate.
+
+Use [`assert_unmoved::assert_pinned_fields`] (requires the `macros` feature)
+instead of depending on this crate directly.
+
+[`assert-unmoved`]: https://docs.rs/assert-unmoved
+[`assert_unmoved::assert_pinned_fields`]: https://docs.rs/assert-unmoved/latest/assert_unmoved/attr.assert_pinned_fields.html
+*/
+
+#![doc(test(
+    no_crate_inject,
+    attr(
+        deny(warnings, rust_2018_idioms, single_use_lifetimes),
+        allow(dead_code, unused_variables)
+    )
+))]
+#![forbid(unsafe_code)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Error, Fields, Item, Result};
+
+/// An attribute that wraps all `#[pin]` fields of a struct or enum in
+/// [`AssertUnmoved`] when compiled with `cfg(test)`.
+///
+/// This is intended to be used with [pin-project]'s `#[pin_project]`
+/// attribute, and must be placed before it. Without `cfg(test)`, the item is
+/// left as is.
+///
+/// Since the pinned fields have type `AssertUnmoved<T>` in tests, initialize
+/// them with `.into()`, which works both with and without `cfg(test)`. For
+/// generic types, do this in a constructor so that the field types can be
+/// inferred.
+/// Pinned projections of these fields are `Pin<&mut AssertUnmoved<T>>`, which
+/// implements the same traits as `T` (e.g., `Future`) and panics if the
+/// field moved after being pinned and mutably accessed.
+///
+/// # Examples
+///
+/// ```
+/// use std::{
+///     future::Future,
+///     pin::Pin,
+///     task::{Context, Poll},
+/// };
+///
+/// use assert_unmoved::assert_pinned_fields;
+/// use pin_project::pin_project;
+///
+/// #[assert_pinned_fields]
+/// #[pin_project]
+/// struct Timeout<F, T> {
+///     #[pin]
+///     future: F,
+///     #[pin]
+///     timer: T,
+/// }
+///
+/// impl<F: Future, T: Future<Output = ()>> Future for Timeout<F, T> {
+///     type Output = Option<F::Output>;
+///
+///     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+///         let this = self.project();
+///         if let Poll::Ready(v) = this.future.poll(cx) {
+///             return Poll::Ready(Some(v));
+///         }
+///         this.timer.poll(cx).map(|()| None)
+///     }
+/// }
+///
+/// impl<F, T> Timeout<F, T> {
+///     fn new(future: F, timer: T) -> Self {
+///         Self { future: future.into(), timer: timer.into() }
+///     }
+/// }
+///
+/// let timeout = Timeout::new(async { 1 }, async {});
+/// ```
+///
+/// # Limitations
+///
+/// `cfg(test)` is evaluated in the crate that defines the type, so the fields
+/// are only wrapped when that crate itself is compiled for tests, e.g., in
+/// the unit tests of a library, or in an integration test that defines the
+/// type. A type defined in a library is not wrapped in the library's
+/// integration tests or in the tests of other crates, since the library is
+/// compiled without `cfg(test)` there. To check such a type, wrap the fields
+/// in `AssertUnmoved` behind a feature of your crate instead, as described in
+/// the documentation of [`NoCheck`].
+///
+/// [`AssertUnmoved`]: https://docs.rs/assert-unmoved/latest/assert_unmoved/struct.AssertUnmoved.html
+/// [`NoCheck`]: https://docs.rs/assert-unmoved/latest/assert_unmoved/struct.NoCheck.html#switching-with-a-feature
+/// [pin-project]: https://docs.rs/pin-project
+#[proc_macro_attribute]
+pub fn assert_pinned_fields(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = TokenStream2::from(args);
+    if !args.is_empty() {
+        return Error::new_spanned(args, "unexpected argument").into_compile_error().into();
+    }
+    let item = parse_macro_input!(input as Item);
+    expand(item).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand(item: Item) -> Result<TokenStream2> {
+    let mut wrapped = item.clone();
+    let (ident, count) = match &mut wrapped {
+        Item::Struct(item) => (&item.ident, wrap_pinned_fields(&mut item.fields)),
+        Item::Enum(item) => {
+            (&item.ident, item.variants.iter_mut().map(|v| wrap_pinned_fields(&mut v.fields)).sum())
+        }
+        _ => {
+            return Err(Error::new_spanned(
+                item,
+                "#[assert_pinned_fields] may only be used on structs or enums",
+            ));
+        }
+    };
+    if count == 0 {
+        return Err(Error::new_spanned(
+            ident,
+            "#[assert_pinned_fields] requires at least one #[pin] field",
+        ));
+    }
+    Ok(quote! {
+        #[cfg(test)]
+        #wrapped
+        #[cfg(not(test))]
+        #item
+    })
+}
+
+/// Wraps the types of `#[pin]` fields in `AssertUnmoved`, and returns the
+/// number of wrapped fields.
+fn wrap_pinned_fields(fields: &mut Fields) -> usize {
+    let mut count = 0;
+    for field in fields.iter_mut() {
+        if field.attrs.iter().any(|attr| attr.path().is_ident("pin")) {
+            let ty = &field.ty;
+            field.ty = parse_quote!(::assert_unmoved::AssertUnmoved<#ty>);
+            count += 1;
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::parse_quote;
+
+    use super::expand;
+
+    #[test]
+    fn struct_() {
+        let item = parse_quote! {
+            #[pin_project]
+            struct Select<A, B> {
+                #[pin]
+                a: A,
+                #[pin]
+                b: B,
+                polled: usize,
+            }
+        };
+        let expected = quote! {
+            #[cfg(test)]
+            #[pin_project]
+            struct Select<A, B> {
+                #[pin]
+                a: ::assert_unmoved::AssertUnmoved<A>,
+                #[pin]
+                b: ::assert_unmoved::AssertUnmoved<B>,
+                polled: usize,
+            }
+            #[cfg(not(test))]
+            #[pin_project]
+            struct Select<A, B> {
+                #[pin]
+                a: A,
+                #[pin]
+                b: B,
+                polled: usize,
+            }
+        };
+        assert_eq!(expand(item).unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn enum_() {
+        let item = parse_quote! {
+            #[pin_project(project = EitherProj)]
+            enum Either<A, B> {
+                Left(#[pin] A),
+                Right(#[pin] B),
+                Neither,
+            }
+        };
+        let expected = quote! {
+            #[cfg(test)]
+            #[pin_project(project = EitherProj)]
+            enum Either<A, B> {
+                Left(#[pin] ::assert_unmoved::AssertUnmoved<A>),
+                Right(#[pin] ::assert_unmoved::AssertUnmoved<B>),
+                Neither,
+            }
+            #[cfg(not(test))]
+            #[pin_project(project = EitherProj)]
+            enum Either<A, B> {
+                Left(#[pin] A),
+                Right(#[pin] B),
+                Neither,
+            }
+        };
+        assert_eq!(expand(item).unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn errors() {
+        let item = parse_quote! {
+            #[pin_project]
+            struct S<T> {
+                inner: T,
+            }
+        };
+        assert_eq!(
+            expand(item).unwrap_err().to_string(),
+            "#[assert_pinned_fields] requires at least one #[pin] field"
+        );
+        let item = parse_quote! {
+            fn f() {}
+        };
+        assert_eq!(
+            expand(item).unwrap_err().to_string(),
+            "#[assert_pinned_fields] may only be used on structs or enums"
+        );
+    }
+}
@@ -1,67 +1,299 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, rc::Rc, sync::Arc};
 use core::{
     future::Future,
+    mem::{self, ManuallyDrop},
     ops,
     panic::Location,
     pin::Pin,
+    ptr,
     task::{Context, Poll},
 };
-use std::thread;
 
 use pin_project_lite::pin_project;
 
+use crate::error::{MoveError, MoveErrorKind};
+
 pin_project! {
     /// A type that asserts that the underlying type is not moved after being pinned
     /// and mutably accessed.
     ///
+    /// Only the address is tracked, so accessing the pinned value from
+    /// different threads (e.g., when a task migrates between worker threads
+    /// of a multi-threaded runtime) is fine as long as the value itself is
+    /// not moved.
+    ///
+    /// `AssertUnmoved<T>` is always `!Unpin`, even if `T` is `Unpin`, so wrapping
+    /// an `Unpin` type (e.g., `future::Pending`) is still useful: it lets you
+    /// check that code which is generic over `!Unpin` types does not move them.
+    ///
+    /// Moves are detected even if `T` is a zero-sized type: `AssertUnmoved<T>`
+    /// itself is never zero-sized, so its address changes when it is moved.
+    ///
+    /// `T` can be a dynamically sized type such as a trait object, e.g.,
+    /// `Pin<Box<AssertUnmoved<F>>>` can be coerced to
+    /// `Pin<Box<AssertUnmoved<dyn Future<Output = F::Output>>>>`.
+    ///
     /// See crate level documentation for details.
     #[project(!Unpin)]
     #[derive(Debug)]
-    pub struct AssertUnmoved<T> {
-        #[pin]
-        inner: T,
+    pub struct AssertUnmoved<T: ?Sized> {
+        // The address recorded when first pinned and mutably accessed, or 0.
+        // The lowest bit is `VIOLATION_REPORTED`.
         this_addr: usize,
         first_pinned_mutably_accessed_at: Option<&'static Location<'static>>,
+        tag: Option<&'static &'static str>,
+        get_pin_mut_calls: CallCount,
+        // This must be the last field to allow `T: ?Sized`.
+        #[pin]
+        inner: T,
     }
-    impl<T> PinnedDrop for AssertUnmoved<T> {
+    impl<T: ?Sized> PinnedDrop for AssertUnmoved<T> {
         /// # Panics
         ///
         /// Panics if this `AssertUnmoved` moved after being pinned and mutably accessed.
         fn drop(this: Pin<&mut Self>) {
+            if this.violation_reported() {
+                return;
+            }
             // If the thread is panicking then we can't panic again as that will
             // cause the process to be aborted.
-            if !thread::panicking() && this.this_addr != 0 {
-                let cur_this = this.addr();
-                assert_eq!(
-                    this.this_addr,
-                    cur_this,
-                    "AssertUnmoved moved before drop\n\
-                     \tfirst pinned mutably accessed at {}\n",
-                    this.first_pinned_mutably_accessed_at.unwrap()
-                );
+            #[cfg(feature = "std")]
+            if std::thread::panicking() {
+                if crate::strict_drop::enabled() {
+                    if let Err(e) = this.check_unmoved(MoveErrorKind::BeforeDrop, None) {
+                        report_move_while_panicking(&e);
+                    }
+                }
+                return;
+            }
+            // Without std, there is no way to know whether the thread is
+            // panicking, so check only if panics abort, in which case drop
+            // never runs during unwinding.
+            #[cfg(not(feature = "std"))]
+            if !cfg!(panic = "abort") {
+                return;
+            }
+            if let Err(e) = this.check_unmoved(MoveErrorKind::BeforeDrop, None) {
+                report_move_on_drop(&e);
             }
         }
     }
 }
 
+// Layout of `AssertUnmoved<T>`:
+//
+// - `this_addr: usize`, including the `VIOLATION_REPORTED` bit (1 word)
+// - `first_pinned_mutably_accessed_at: Option<&'static Location<'static>>` (1 word)
+// - `tag: Option<&'static &'static str>` (1 word)
+// - `get_pin_mut_calls: CallCount` (1 word with the `testing` feature, 0 otherwise)
+// - `inner: T`
+//
+// Make sure the overhead of tracking stays within these words. If you add a
+// field, update this check and the `size` test in tests/test.rs.
+const _: [(); 0
+    - (mem::size_of::<AssertUnmoved<()>>()
+        > (3 + cfg!(feature = "testing") as usize) * mem::size_of::<usize>()) as usize] = [];
+
+// Set in `this_addr` when a move has already been reported by `get_mut` or
+// `get_pin_mut`. If it is set, the check on drop is skipped to avoid reporting
+// the move twice (e.g., when the first panic was caught by `catch_unwind`).
+//
+// `AssertUnmoved` contains a `usize`, so its address is a multiple of the
+// alignment of `usize`, and the lowest bit of the recorded address is always
+// free as long as that alignment is at least 2.
+const VIOLATION_REPORTED: usize = 1;
+const _: [(); 0 - (mem::align_of::<AssertUnmoved<()>>() < 2) as usize] = [];
+
+/// The number of calls to `get_pin_mut`, which is only counted with the
+/// `testing` feature so that `AssertUnmoved` does not pay for it otherwise.
+#[derive(Debug, Clone, Copy)]
+struct CallCount {
+    #[cfg(feature = "testing")]
+    count: usize,
+}
+
+impl CallCount {
+    const fn new() -> Self {
+        Self {
+            #[cfg(feature = "testing")]
+            count: 0,
+        }
+    }
+
+    #[allow(clippy::unused_self)]
+    fn increment(&mut self) {
+        #[cfg(feature = "testing")]
+        {
+            self.count += 1;
+        }
+    }
+}
+
 impl<T> AssertUnmoved<T> {
     /// Creates a new `AssertUnmoved`.
+    ///
+    /// This is a `const fn`, so it can be used in `const` and `static` items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use assert_unmoved::AssertUnmoved;
+    ///
+    /// static X: AssertUnmoved<i32> = AssertUnmoved::new(42);
+    /// assert_eq!(*X, 42);
+    /// ```
     #[must_use]
     pub const fn new(inner: T) -> Self {
-        Self { inner, this_addr: 0, first_pinned_mutably_accessed_at: None }
+        Self {
+            this_addr: 0,
+            first_pinned_mutably_accessed_at: None,
+            tag: None,
+            get_pin_mut_calls: CallCount::new(),
+            inner,
+        }
+    }
+
+    /// Creates a new `AssertUnmoved` with the given tag.
+    ///
+    /// The tag is included in panic messages (e.g., `AssertUnmoved[my_stream]
+    /// moved between get_pin_mut calls`), which helps to tell which instance
+    /// moved when a test uses many of them.
+    ///
+    /// The tag is taken by a double reference (e.g., `&"my_stream"`) so that
+    /// `AssertUnmoved` stores it in a single word. Note that this word is
+    /// stored even by `AssertUnmoved`s created without a tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use assert_unmoved::AssertUnmoved;
+    ///
+    /// let v = AssertUnmoved::with_tag(1, &"my_value");
+    /// assert_eq!(*v, 1);
+    /// ```
+    #[must_use]
+    pub const fn with_tag(inner: T, tag: &'static &'static str) -> Self {
+        Self {
+            this_addr: 0,
+            first_pinned_mutably_accessed_at: None,
+            tag: Some(tag),
+            get_pin_mut_calls: CallCount::new(),
+            inner,
+        }
+    }
+
+    /// Creates a new `AssertUnmoved` in a [`Box`].
+    ///
+    /// This is equivalent to [`Box::new(AssertUnmoved::new(inner))`](AssertUnmoved::new).
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    pub fn new_box(inner: T) -> Box<Self> {
+        Box::new(Self::new(inner))
+    }
+
+    /// Creates a new pinned `AssertUnmoved` in a [`Box`].
+    ///
+    /// This is equivalent to [`Box::pin(AssertUnmoved::new(inner))`](AssertUnmoved::new).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{future::Future, task::Context};
+    ///
+    /// use assert_unmoved::AssertUnmoved;
+    /// use futures::{future, task::noop_waker};
+    ///
+    /// let waker = noop_waker();
+    /// let mut cx = Context::from_waker(&waker);
+    ///
+    /// let mut future = AssertUnmoved::pin_box(future::pending::<()>());
+    /// assert!(future.as_mut().poll(&mut cx).is_pending());
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    pub fn pin_box(inner: T) -> Pin<Box<Self>> {
+        Box::pin(Self::new(inner))
+    }
+
+    /// Creates a new pinned `AssertUnmoved` in a [`Rc`].
+    ///
+    /// This is equivalent to [`Rc::pin(AssertUnmoved::new(inner))`](AssertUnmoved::new).
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    pub fn pin_rc(inner: T) -> Pin<Rc<Self>> {
+        Rc::pin(Self::new(inner))
+    }
+
+    /// Creates a new pinned `AssertUnmoved` in a [`Arc`].
+    ///
+    /// This is equivalent to [`Arc::pin(AssertUnmoved::new(inner))`](AssertUnmoved::new).
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    pub fn pin_arc(inner: T) -> Pin<Arc<Self>> {
+        Arc::pin(Self::new(inner))
     }
+}
 
+impl<T: ?Sized> AssertUnmoved<T> {
     /// Gets a reference to the underlying type.
     ///
     /// Unlike [`get_mut`](AssertUnmoved::get_mut) method, this method can always called.
     ///
+    /// This does not check whether this `AssertUnmoved` moved, since a moved
+    /// value cannot be observed as moved through a shared reference. The same
+    /// applies to trait methods that take `&self` (e.g.,
+    /// `FusedFuture::is_terminated`, `Iterator::size_hint`, and
+    /// `Stream::size_hint`), which go through this method. A move is still
+    /// detected on the next mutable access or on drop.
+    ///
     /// You can also access the underlying type via [`Deref`](std::ops::Deref) impl.
     #[must_use]
     pub const fn get_ref(&self) -> &T {
         &self.inner
     }
 
+    /// Calls the given closure with a reference to the underlying type.
+    ///
+    /// This is equivalent to `f(this.get_ref())`, and like
+    /// [`get_ref`](AssertUnmoved::get_ref), does not start or check tracking of
+    /// the address.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `AssertUnmoved::inspect(this, f)` to avoid conflicts with methods on
+    /// the underlying type and extension traits (e.g., `Iterator::inspect`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use assert_unmoved::AssertUnmoved;
+    ///
+    /// let v = AssertUnmoved::new(vec![1, 2]);
+    /// assert_eq!(AssertUnmoved::inspect(&v, Vec::len), 2);
+    /// ```
+    pub fn inspect<R, F: FnOnce(&T) -> R>(this: &Self, f: F) -> R {
+        f(this.get_ref())
+    }
+
+    /// Calls the given closure with a pinned reference to the underlying type.
+    ///
+    /// This is equivalent to `f(AssertUnmoved::as_inner_pin(this))`, and like
+    /// [`as_inner_pin`](AssertUnmoved::as_inner_pin), does not start or check
+    /// tracking of the address.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `AssertUnmoved::inspect_pin_ref(this, f)`, like
+    /// [`inspect`](AssertUnmoved::inspect).
+    pub fn inspect_pin_ref<R, F: FnOnce(Pin<&T>) -> R>(this: Pin<&Self>, f: F) -> R {
+        f(Self::as_inner_pin(this))
+    }
+
     /// Gets a mutable reference to the underlying type.
     ///
     /// Note that this method can only be called before pinned since
@@ -70,18 +302,39 @@ impl<T> AssertUnmoved<T> {
     /// # Panics
     ///
     /// Panics if this `AssertUnmoved` moved after being pinned and mutably accessed.
+    ///
+    /// # Examples
+    ///
+    /// Types that own an `AssertUnmoved`, such as [`Cursor`](std::io::Cursor),
+    /// access it through `&mut` rather than pinned references. Move detection
+    /// never fires in such compositions, even if the owner is moved, because
+    /// `AssertUnmoved` is never pinned and mutably accessed.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    ///
+    /// use assert_unmoved::AssertUnmoved;
+    ///
+    /// let mut cursor = Cursor::new(AssertUnmoved::new(vec![1_u8, 2, 3]));
+    /// cursor.set_position(1);
+    /// // `Cursor`'s `Read`, `Write`, and `Seek` impls are only available for
+    /// // buffer types such as `Vec<u8>`, so access the buffer via
+    /// // `get_ref`/`get_mut`.
+    /// cursor.get_mut().get_mut().push(4);
+    ///
+    /// // This doesn't panic.
+    /// let cursor = Box::new(cursor);
+    /// assert_eq!(cursor.get_ref()[cursor.position() as usize..], [2, 3, 4]);
+    /// ```
     #[must_use]
     #[track_caller]
     pub fn get_mut(&mut self) -> &mut T {
-        if self.this_addr != 0 {
-            let cur_this = self.addr();
-            assert_eq!(
-                self.this_addr,
-                cur_this,
-                "AssertUnmoved moved after get_pin_mut call\n\
-                 \tfirst pinned mutably accessed at {}\n",
-                self.first_pinned_mutably_accessed_at.unwrap()
-            );
+        if let Err(mut e) =
+            self.check_unmoved(MoveErrorKind::AfterGetPinMutCall, Some(Location::caller()))
+        {
+            e.method = Some("get_mut");
+            self.this_addr |= VIOLATION_REPORTED;
+            report_move(&e);
         }
         &mut self.inner
     }
@@ -123,455 +376,642 @@ impl<T> AssertUnmoved<T> {
     #[must_use]
     #[track_caller]
     pub fn get_pin_mut(mut self: Pin<&mut Self>) -> Pin<&mut T> {
-        let cur_this = self.addr();
-        if self.this_addr == 0 {
-            // First time being pinned and mutably accessed.
-            *self.as_mut().project().this_addr = cur_this;
-            *self.as_mut().project().first_pinned_mutably_accessed_at = Some(Location::caller());
-        } else {
-            assert_eq!(
-                self.this_addr,
-                cur_this,
-                "AssertUnmoved moved between get_pin_mut calls\n\
-                 \tfirst pinned mutably accessed at {}\n",
-                self.first_pinned_mutably_accessed_at.unwrap()
-            );
+        if let Err(e) = self.as_mut().try_get_pin_mut() {
+            report_move(&e);
         }
         self.project().inner
     }
 
-    fn addr(&self) -> usize {
-        self as *const Self as usize
+    /// Tries to get a pinned mutable reference to the underlying type.
+    ///
+    /// This is the same as [`get_pin_mut`](AssertUnmoved::get_pin_mut) method,
+    /// except that this returns an error instead of panicking when a move is
+    /// detected. This is useful to collect violations instead of aborting the
+    /// test on the first one.
+    ///
+    /// Once an error has been returned, the move is considered reported and
+    /// will not be reported again on drop.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MoveError`] if this `AssertUnmoved` moved after being pinned
+    /// and mutably accessed.
+    #[track_caller]
+    pub fn try_get_pin_mut(mut self: Pin<&mut Self>) -> Result<Pin<&mut T>, MoveError> {
+        self.as_mut().project().get_pin_mut_calls.increment();
+        self.as_mut().track(Location::caller())?;
+        Ok(self.project().inner)
     }
-}
-
-impl<T> ops::Deref for AssertUnmoved<T> {
-    type Target = T;
 
-    fn deref(&self) -> &Self::Target {
-        self.get_ref()
+    /// Starts tracking the current address of this `AssertUnmoved` without
+    /// accessing the underlying value.
+    ///
+    /// This is useful in two-step initialization patterns, where a value is
+    /// constructed, then pinned in its final location (e.g., on the heap), and
+    /// only accessed later: after calling this method, moving this
+    /// `AssertUnmoved` is detected even if it is never accessed.
+    ///
+    /// Unlike [`reset_pin_state`](AssertUnmoved::reset_pin_state), this cannot
+    /// hide a move. If this `AssertUnmoved` has already been pinned and mutably
+    /// accessed, this only checks that it has not been moved since then. This
+    /// is not counted by `get_pin_mut_call_count`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `AssertUnmoved` moved after being pinned and mutably accessed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use assert_unmoved::AssertUnmoved;
+    ///
+    /// let v = AssertUnmoved::new(1);
+    /// let mut v = Box::pin(v);
+    /// v.as_mut().snapshot_addr();
+    /// assert!(v.is_pinned_accessed());
+    /// ```
+    #[track_caller]
+    pub fn snapshot_addr(self: Pin<&mut Self>) {
+        if let Err(e) = self.track(Location::caller()) {
+            report_move(&e);
+        }
     }
-}
 
-impl<T> From<T> for AssertUnmoved<T> {
-    /// Converts a `T` into a `AssertUnmoved<T>`.
+    /// Polls the underlying future without requiring the caller to pin
+    /// `AssertUnmoved`.
     ///
-    /// This is equivalent to [`AssertUnmoved::new`].
-    fn from(inner: T) -> Self {
-        Self::new(inner)
+    /// This is a shorthand for
+    /// `unsafe { Pin::new_unchecked(&mut future) }.poll(cx)` in tests. Like
+    /// [`get_pin_mut`](AssertUnmoved::get_pin_mut), the first call starts
+    /// tracking the address of this `AssertUnmoved`, and subsequent calls,
+    /// [`get_mut`](AssertUnmoved::get_mut), and drop check that it has not
+    /// been moved since then.
+    ///
+    /// Prefer `pin_and_poll!` or `testing::poll_once` (requires the `testing`
+    /// feature), which poll a future that is actually pinned, if possible.
+    ///
+    /// # Safety
+    ///
+    /// This pins `AssertUnmoved` in place, so the caller must guarantee that
+    /// it is not moved between calls or before drop, as with
+    /// [`Pin::new_unchecked`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `AssertUnmoved` moved after being pinned and mutably accessed.
+    #[track_caller]
+    pub unsafe fn poll_once_unchecked(&mut self, cx: &mut Context<'_>) -> Poll<T::Output>
+    where
+        T: Future,
+    {
+        // SAFETY: the caller must guarantee that this `AssertUnmoved` is not
+        // moved after this call.
+        unsafe { Pin::new_unchecked(self) }.get_pin_mut().poll(cx)
     }
-}
 
-impl<T: Default> Default for AssertUnmoved<T> {
-    /// Creates a new `AssertUnmoved`, with the default value for `T`.
+    /// Gets a pinned reference to the underlying type.
     ///
-    /// This is equivalent to [`AssertUnmoved::new(T::default())`](AssertUnmoved::new).
-    fn default() -> Self {
-        Self::new(T::default())
+    /// Unlike [`get_pin_mut`](AssertUnmoved::get_pin_mut) method, this does not
+    /// start or check tracking of the address.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `AssertUnmoved::as_inner_pin(this)` to avoid conflicts with methods on
+    /// the underlying type accessed via [`Deref`](std::ops::Deref) impl.
+    #[must_use]
+    pub fn as_inner_pin(this: Pin<&Self>) -> Pin<&T> {
+        this.project_ref().inner
     }
-}
-
-impl<F: Future> Future for AssertUnmoved<F> {
-    type Output = F::Output;
 
-    #[track_caller]
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        self.get_pin_mut().poll(cx)
+    /// Returns `true` if this `AssertUnmoved` has been pinned and mutably accessed.
+    ///
+    /// Once this returns `true`, moving this `AssertUnmoved` will be detected.
+    #[must_use]
+    pub const fn is_pinned_accessed(&self) -> bool {
+        self.recorded_addr() != 0
     }
-}
 
-#[cfg(feature = "futures03")]
-#[cfg_attr(docsrs, doc(cfg(feature = "futures03")))]
-mod futures03 {
-    use core::{
-        pin::Pin,
-        task::{Context, Poll},
-    };
-
-    use futures_core::{
-        future::FusedFuture,
-        stream::{FusedStream, Stream},
-    };
-    use futures_io as io;
-    use futures_sink::Sink;
-
-    use super::AssertUnmoved;
-
-    impl<F: FusedFuture> FusedFuture for AssertUnmoved<F> {
-        fn is_terminated(&self) -> bool {
-            self.get_ref().is_terminated()
-        }
+    /// Returns the location where this `AssertUnmoved` was first pinned and
+    /// mutably accessed, or `None` if it has not been pinned and mutably
+    /// accessed yet.
+    #[must_use]
+    pub const fn pinned_at_location(&self) -> Option<&'static Location<'static>> {
+        self.first_pinned_mutably_accessed_at
     }
 
-    impl<S: Stream> Stream for AssertUnmoved<S> {
-        type Item = S::Item;
-
-        #[track_caller]
-        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-            self.get_pin_mut().poll_next(cx)
-        }
-
-        fn size_hint(&self) -> (usize, Option<usize>) {
-            self.get_ref().size_hint()
-        }
+    /// Returns the current address of this `AssertUnmoved`.
+    ///
+    /// Together with [`get_recorded_addr`](AssertUnmoved::get_recorded_addr),
+    /// this can be used to write custom assertions or diagnostics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use assert_unmoved::AssertUnmoved;
+    ///
+    /// let mut v = Box::pin(AssertUnmoved::new(1));
+    /// assert_eq!(v.get_recorded_addr(), None);
+    /// let _ = v.as_mut().get_pin_mut();
+    /// assert_eq!(v.get_recorded_addr(), Some(v.get_addr()));
+    /// ```
+    #[must_use]
+    pub fn get_addr(&self) -> usize {
+        self.addr()
     }
 
-    impl<S: FusedStream> FusedStream for AssertUnmoved<S> {
-        fn is_terminated(&self) -> bool {
-            self.get_ref().is_terminated()
+    /// Returns the address recorded when this `AssertUnmoved` was first pinned
+    /// and mutably accessed, or `None` if it has not been pinned and mutably
+    /// accessed yet.
+    #[must_use]
+    pub const fn get_recorded_addr(&self) -> Option<usize> {
+        match self.recorded_addr() {
+            0 => None,
+            addr => Some(addr),
         }
     }
 
-    impl<S: Sink<Item>, Item> Sink<Item> for AssertUnmoved<S> {
-        type Error = S::Error;
-
-        #[track_caller]
-        fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-            self.get_pin_mut().poll_ready(cx)
-        }
-
-        #[track_caller]
-        fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
-            self.get_pin_mut().start_send(item)
-        }
-
-        #[track_caller]
-        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-            self.get_pin_mut().poll_flush(cx)
-        }
-
-        #[track_caller]
-        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-            self.get_pin_mut().poll_close(cx)
-        }
+    /// Returns the number of times this `AssertUnmoved` has been pinned and
+    /// mutably accessed.
+    ///
+    /// This counts calls to [`get_pin_mut`](AssertUnmoved::get_pin_mut) and
+    /// [`try_get_pin_mut`](AssertUnmoved::try_get_pin_mut), including those made
+    /// by trait impls such as [`Future::poll`], so it can be used to assert how
+    /// many times the underlying future or stream was polled.
+    ///
+    /// The count is only kept with the `testing` feature, since it adds a word
+    /// to every `AssertUnmoved`.
+    #[cfg(feature = "testing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+    #[must_use]
+    pub const fn get_pin_mut_call_count(&self) -> usize {
+        self.get_pin_mut_calls.count
     }
 
-    impl<R: io::AsyncRead> io::AsyncRead for AssertUnmoved<R> {
-        #[track_caller]
-        fn poll_read(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            buf: &mut [u8],
-        ) -> Poll<io::Result<usize>> {
-            self.get_pin_mut().poll_read(cx, buf)
-        }
-
-        #[track_caller]
-        fn poll_read_vectored(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            bufs: &mut [io::IoSliceMut<'_>],
-        ) -> Poll<io::Result<usize>> {
-            self.get_pin_mut().poll_read_vectored(cx, bufs)
-        }
+    /// Resets the tracking of the address, as if this `AssertUnmoved` has never
+    /// been pinned and mutably accessed.
+    ///
+    /// This is useful in tests that intentionally move a pinned value (e.g., to
+    /// test recovery logic) and then keep using it. The next call to
+    /// [`get_pin_mut`](AssertUnmoved::get_pin_mut) starts tracking the new
+    /// address. A move that has already been reported and the count returned by
+    /// `get_pin_mut_call_count` are reset too.
+    ///
+    /// # Safety
+    ///
+    /// The caller must guarantee that no pinned references to this
+    /// `AssertUnmoved` or the underlying value obtained before the reset are
+    /// used after the reset, and that the underlying value is sound to keep
+    /// using at its current address.
+    pub unsafe fn reset_pin_state(self: Pin<&mut Self>) {
+        let this = self.project();
+        *this.this_addr = 0;
+        *this.first_pinned_mutably_accessed_at = None;
+        *this.get_pin_mut_calls = CallCount::new();
     }
 
-    impl<W: io::AsyncWrite> io::AsyncWrite for AssertUnmoved<W> {
-        #[track_caller]
-        fn poll_write(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            buf: &[u8],
-        ) -> Poll<io::Result<usize>> {
-            self.get_pin_mut().poll_write(cx, buf)
-        }
+    /// Returns `true` if `self` and `other` are the same `AssertUnmoved`, i.e.,
+    /// they point to the same memory location.
+    ///
+    /// This is equivalent to [`ptr::eq(self, other)`](core::ptr::eq).
+    #[must_use]
+    pub fn is_same_location_as(&self, other: &Self) -> bool {
+        ptr::eq(self, other)
+    }
 
-        #[track_caller]
-        fn poll_write_vectored(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            bufs: &[io::IoSlice<'_>],
-        ) -> Poll<io::Result<usize>> {
-            self.get_pin_mut().poll_write_vectored(cx, bufs)
+    /// Starts tracking the address if this `AssertUnmoved` has never been
+    /// pinned and mutably accessed, or returns an error if it moved since then.
+    fn track(
+        mut self: Pin<&mut Self>,
+        location: &'static Location<'static>,
+    ) -> Result<(), MoveError> {
+        if self.first_pinned_mutably_accessed_at.is_none() {
+            // First time being pinned and mutably accessed.
+            let cur_this = self.addr();
+            *self.as_mut().project().this_addr = cur_this;
+            *self.as_mut().project().first_pinned_mutably_accessed_at = Some(location);
+        } else if let Err(e) =
+            self.check_unmoved(MoveErrorKind::BetweenGetPinMutCalls, Some(location))
+        {
+            *self.as_mut().project().this_addr |= VIOLATION_REPORTED;
+            return Err(e);
         }
+        Ok(())
+    }
 
-        #[track_caller]
-        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-            self.get_pin_mut().poll_flush(cx)
+    /// Returns an error if this `AssertUnmoved` has been pinned and mutably
+    /// accessed, since moving the underlying value out is then a move after
+    /// being pinned.
+    fn check_not_pinned(&self, detected_at: &'static Location<'static>) -> Result<(), MoveError> {
+        match self.first_pinned_mutably_accessed_at {
+            Some(first_pinned_at) => Err(MoveError {
+                original_addr: self.recorded_addr(),
+                current_addr: self.addr(),
+                first_pinned_at,
+                detected_at: Some(detected_at),
+                tag: self.tag.copied(),
+                kind: MoveErrorKind::AfterGetPinMutCall,
+                method: None,
+            }),
+            None => Ok(()),
         }
+    }
 
-        #[track_caller]
-        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-            self.get_pin_mut().poll_close(cx)
+    /// Returns an error if this `AssertUnmoved` moved after being pinned and
+    /// mutably accessed.
+    fn check_unmoved(
+        &self,
+        kind: MoveErrorKind,
+        detected_at: Option<&'static Location<'static>>,
+    ) -> Result<(), MoveError> {
+        let cur_this = self.addr();
+        match self.first_pinned_mutably_accessed_at {
+            Some(first_pinned_at) if self.recorded_addr() != cur_this => Err(MoveError {
+                original_addr: self.recorded_addr(),
+                current_addr: cur_this,
+                first_pinned_at,
+                detected_at,
+                tag: self.tag.copied(),
+                kind,
+                method: None,
+            }),
+            _ => Ok(()),
         }
     }
 
-    impl<S: io::AsyncSeek> io::AsyncSeek for AssertUnmoved<S> {
-        #[track_caller]
-        fn poll_seek(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            pos: io::SeekFrom,
-        ) -> Poll<io::Result<u64>> {
-            self.get_pin_mut().poll_seek(cx, pos)
-        }
+    const fn recorded_addr(&self) -> usize {
+        self.this_addr & !VIOLATION_REPORTED
     }
 
-    impl<R: io::AsyncBufRead> io::AsyncBufRead for AssertUnmoved<R> {
-        #[track_caller]
-        fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
-            self.get_pin_mut().poll_fill_buf(cx)
-        }
+    const fn violation_reported(&self) -> bool {
+        self.this_addr & VIOLATION_REPORTED != 0
+    }
 
-        #[track_caller]
-        fn consume(self: Pin<&mut Self>, amt: usize) {
-            self.get_pin_mut().consume(amt);
-        }
+    fn addr(&self) -> usize {
+        (self as *const Self).cast::<()>() as usize
     }
 }
 
-#[cfg(feature = "tokio02")]
-#[cfg_attr(docsrs, doc(cfg(feature = "tokio02")))]
-mod tokio02 {
-    use core::{
-        mem::MaybeUninit,
-        pin::Pin,
-        task::{Context, Poll},
-    };
-    use std::io;
-
-    use bytes05::{Buf, BufMut};
-    use tokio02_crate::io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite};
-
-    use super::AssertUnmoved;
-
-    impl<R: AsyncRead> AsyncRead for AssertUnmoved<R> {
-        unsafe fn prepare_uninitialized_buffer(&self, buf: &mut [MaybeUninit<u8>]) -> bool {
-            // SAFETY: The safety contract must be upheld by the caller.
-            unsafe { self.get_ref().prepare_uninitialized_buffer(buf) }
-        }
-
-        #[track_caller]
-        fn poll_read(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            buf: &mut [u8],
-        ) -> Poll<io::Result<usize>> {
-            self.get_pin_mut().poll_read(cx, buf)
-        }
-
-        #[track_caller]
-        fn poll_read_buf<B: BufMut>(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            buf: &mut B,
-        ) -> Poll<io::Result<usize>>
-        where
-            Self: Sized,
-        {
-            self.get_pin_mut().poll_read_buf(cx, buf)
-        }
+impl<T> AssertUnmoved<T> {
+    /// Unwraps the value.
+    ///
+    /// This is useful to get the underlying type back after setting it up
+    /// while wrapped in `AssertUnmoved`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `AssertUnmoved` has been pinned and mutably accessed, since
+    /// moving it out is then a move after being pinned.
+    #[must_use]
+    #[track_caller]
+    pub fn into_inner(self) -> T {
+        let this = ManuallyDrop::new(self);
+        if let Err(e) = this.check_not_pinned(Location::caller()) {
+            report_move(&e);
+        }
+        // SAFETY: `this` is wrapped in `ManuallyDrop` and never used after
+        // this read, so `inner` is not dropped twice.
+        unsafe { ptr::read(&this.inner) }
     }
 
-    impl<W: AsyncWrite> AsyncWrite for AssertUnmoved<W> {
-        #[track_caller]
-        fn poll_write(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            buf: &[u8],
-        ) -> Poll<io::Result<usize>> {
-            self.get_pin_mut().poll_write(cx, buf)
-        }
-
-        #[track_caller]
-        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-            self.get_pin_mut().poll_flush(cx)
-        }
-
-        #[track_caller]
-        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-            self.get_pin_mut().poll_shutdown(cx)
-        }
-
-        #[track_caller]
-        fn poll_write_buf<B: Buf>(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            buf: &mut B,
-        ) -> Poll<Result<usize, io::Error>>
-        where
-            Self: Sized,
-        {
-            self.get_pin_mut().poll_write_buf(cx, buf)
-        }
+    /// Unwraps the value if this `AssertUnmoved` has never been pinned and
+    /// mutably accessed.
+    ///
+    /// This is the same as [`into_inner`](AssertUnmoved::into_inner) method,
+    /// except that this returns `self` back instead of panicking if this
+    /// `AssertUnmoved` has been pinned and mutably accessed.
+    ///
+    /// Note that passing `self` by value already moves it, so the returned
+    /// `AssertUnmoved` will report the move on the next mutable access or on
+    /// drop.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use assert_unmoved::AssertUnmoved;
+    ///
+    /// let v = AssertUnmoved::new(vec![1]);
+    /// assert_eq!(v.into_inner_if_unpinned().unwrap(), [1]);
+    /// ```
+    pub fn into_inner_if_unpinned(self) -> Result<T, Self> {
+        if self.first_pinned_mutably_accessed_at.is_some() {
+            return Err(self);
+        }
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is wrapped in `ManuallyDrop` and never used after
+        // this read, so `inner` is not dropped twice.
+        Ok(unsafe { ptr::read(&this.inner) })
     }
 
-    impl<S: AsyncSeek> AsyncSeek for AssertUnmoved<S> {
-        #[track_caller]
-        fn start_seek(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            pos: io::SeekFrom,
-        ) -> Poll<io::Result<()>> {
-            self.get_pin_mut().start_seek(cx, pos)
-        }
-
-        #[track_caller]
-        fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
-            self.get_pin_mut().poll_complete(cx)
+    /// Replaces the underlying value with the given value, returning the old
+    /// value.
+    ///
+    /// This is useful to reuse an `AssertUnmoved` with another underlying
+    /// value (e.g., another future) in test setups.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `AssertUnmoved` has been pinned and mutably accessed, since
+    /// moving the old value out is then a move after being pinned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use assert_unmoved::AssertUnmoved;
+    ///
+    /// let mut v = AssertUnmoved::new(1);
+    /// assert_eq!(v.replace_inner(2), 1);
+    /// assert_eq!(*v, 2);
+    /// ```
+    #[track_caller]
+    pub fn replace_inner(&mut self, inner: T) -> T {
+        if let Err(e) = self.check_not_pinned(Location::caller()) {
+            report_move(&e);
         }
+        mem::replace(&mut self.inner, inner)
     }
 
-    impl<R: AsyncBufRead> AsyncBufRead for AssertUnmoved<R> {
-        #[track_caller]
-        fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
-            self.get_pin_mut().poll_fill_buf(cx)
-        }
+    /// Maps the underlying value to a new value of possibly another type.
+    ///
+    /// This is useful to configure a value after wrapping it in
+    /// `AssertUnmoved` but before pinning it. The returned `AssertUnmoved`
+    /// has never been pinned and mutably accessed. The tag, if any, is kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `AssertUnmoved` has been pinned and mutably accessed, since
+    /// moving the value out is then a move after being pinned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use assert_unmoved::AssertUnmoved;
+    ///
+    /// let v = AssertUnmoved::new(vec![1, 2]);
+    /// let v: AssertUnmoved<usize> = v.map_inner(|v| v.len());
+    /// assert_eq!(*v, 2);
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn map_inner<U, F: FnOnce(T) -> U>(self, f: F) -> AssertUnmoved<U> {
+        let tag = self.tag;
+        let mut mapped = AssertUnmoved::new(f(self.into_inner()));
+        mapped.tag = tag;
+        mapped
+    }
 
-        #[track_caller]
-        fn consume(self: Pin<&mut Self>, amt: usize) {
-            self.get_pin_mut().consume(amt);
-        }
+    /// Moves this `AssertUnmoved` into a pinned [`Box`].
+    ///
+    /// Unlike [`pin_box`](AssertUnmoved::pin_box), this takes an existing
+    /// `AssertUnmoved`, e.g., one that has been set up on the stack before being
+    /// polled. The tag, if any, is kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `AssertUnmoved` has been pinned and mutably accessed, since
+    /// moving it to the heap is then a move after being pinned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{future::Future, task::Context};
+    ///
+    /// use assert_unmoved::AssertUnmoved;
+    /// use futures::{future, task::noop_waker};
+    ///
+    /// let waker = noop_waker();
+    /// let mut cx = Context::from_waker(&waker);
+    ///
+    /// let future = AssertUnmoved::with_tag(future::pending::<()>(), &"pending");
+    /// let mut future = future.into_pin_box();
+    /// assert!(future.as_mut().poll(&mut cx).is_pending());
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    #[track_caller]
+    pub fn into_pin_box(self) -> Pin<Box<Self>> {
+        Box::pin(self.map_inner(|inner| inner))
     }
 }
 
-#[cfg(feature = "tokio03")]
-#[cfg_attr(docsrs, doc(cfg(feature = "tokio03")))]
-mod tokio03 {
-    use core::{
-        pin::Pin,
-        task::{Context, Poll},
-    };
-
-    use tokio03_crate::io;
-
-    use super::AssertUnmoved;
-
-    impl<R: io::AsyncRead> io::AsyncRead for AssertUnmoved<R> {
-        #[track_caller]
-        fn poll_read(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            buf: &mut io::ReadBuf<'_>,
-        ) -> Poll<io::Result<()>> {
-            self.get_pin_mut().poll_read(cx, buf)
-        }
+impl<T> AssertUnmoved<AssertUnmoved<T>> {
+    /// Converts an `AssertUnmoved<AssertUnmoved<T>>` into an `AssertUnmoved<T>`.
+    ///
+    /// This is useful when a value has been wrapped twice by accident, e.g.,
+    /// when a helper function returns `AssertUnmoved<T>` and the caller wraps
+    /// it again. The tag of the inner `AssertUnmoved` is kept, or the tag of
+    /// the outer one if the inner one has no tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either `AssertUnmoved` has been pinned and mutably accessed,
+    /// since moving the value out is then a move after being pinned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use assert_unmoved::AssertUnmoved;
+    ///
+    /// let v = AssertUnmoved::new(AssertUnmoved::new(1));
+    /// let v: AssertUnmoved<i32> = v.flatten();
+    /// assert_eq!(*v, 1);
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn flatten(self) -> AssertUnmoved<T> {
+        let outer_tag = self.tag;
+        let inner = self.into_inner();
+        let tag = inner.tag.or(outer_tag);
+        let mut flattened = AssertUnmoved::new(inner.into_inner());
+        flattened.tag = tag;
+        flattened
     }
+}
 
-    impl<W: io::AsyncWrite> io::AsyncWrite for AssertUnmoved<W> {
-        #[track_caller]
-        fn poll_write(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            buf: &[u8],
-        ) -> Poll<io::Result<usize>> {
-            self.get_pin_mut().poll_write(cx, buf)
-        }
-
-        #[track_caller]
-        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-            self.get_pin_mut().poll_flush(cx)
-        }
+/// Reports a detected move and panics.
+///
+/// This panics even in soft mode: the callers would otherwise go on to hand out
+/// a reference to the moved value, which is undefined behavior for
+/// self-referential types.
+#[cold]
+fn report_move(e: &MoveError) {
+    log_move(e);
+    panic!("{}\n", e);
+}
 
-        #[track_caller]
-        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-            self.get_pin_mut().poll_shutdown(cx)
+/// Reports a move detected on drop and panics, or calls the soft mode handler
+/// if soft mode is enabled.
+///
+/// Unlike `report_move`, nothing is accessed through this `AssertUnmoved`
+/// after this, so it is sound to continue.
+#[cold]
+fn report_move_on_drop(e: &MoveError) {
+    log_move(e);
+    #[cfg(feature = "std")]
+    {
+        if crate::soft_mode::enabled() {
+            crate::soft_mode::handle(*e);
+            return;
         }
     }
+    panic!("{}\n", e);
+}
 
-    impl<S: io::AsyncSeek> io::AsyncSeek for AssertUnmoved<S> {
-        #[track_caller]
-        fn start_seek(self: Pin<&mut Self>, pos: io::SeekFrom) -> io::Result<()> {
-            self.get_pin_mut().start_seek(pos)
-        }
-
-        #[track_caller]
-        fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
-            self.get_pin_mut().poll_complete(cx)
-        }
-    }
+/// Reports a move detected on drop while the thread is panicking, in strict
+/// drop mode.
+///
+/// Panicking again would abort the process, so this calls the soft mode
+/// handler, which prints the error to stderr by default, instead.
+#[cfg(feature = "std")]
+#[cold]
+fn report_move_while_panicking(e: &MoveError) {
+    log_move(e);
+    crate::soft_mode::handle(*e);
+}
 
-    impl<R: io::AsyncBufRead> io::AsyncBufRead for AssertUnmoved<R> {
-        #[track_caller]
-        fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
-            self.get_pin_mut().poll_fill_buf(cx)
-        }
+/// Emits the error to the enabled logging integrations.
+#[cfg_attr(not(any(feature = "tracing01", feature = "log04")), allow(unused_variables))]
+fn log_move(e: &MoveError) {
+    #[cfg(feature = "tracing01")]
+    tracing::error!(
+        original_addr = e.original_addr,
+        current_addr = e.current_addr,
+        first_pinned_at = %e.first_pinned_at,
+        detected_at = e.detected_at.map(tracing::field::display),
+        tag = e.tag,
+        "{}",
+        e
+    );
+    #[cfg(feature = "log04")]
+    log::error!("{}", e);
+}
 
-        #[track_caller]
-        fn consume(self: Pin<&mut Self>, amt: usize) {
-            self.get_pin_mut().consume(amt);
+#[cfg(feature = "futures-core03")]
+impl<T: ?Sized> AssertUnmoved<T> {
+    /// Same as `get_pin_mut`, but includes the name of the given trait
+    /// method in the panic message when a move is detected.
+    #[track_caller]
+    fn get_pin_mut_in(mut self: Pin<&mut Self>, method: &'static str) -> Pin<&mut T> {
+        if let Err(mut e) = self.as_mut().try_get_pin_mut() {
+            e.method = Some(method);
+            report_move(&e);
         }
+        self.project().inner
     }
 }
 
-#[cfg(feature = "tokio1")]
-#[cfg_attr(docsrs, doc(cfg(feature = "tokio1")))]
-mod tokio1 {
-    use core::{
-        pin::Pin,
-        task::{Context, Poll},
-    };
-
-    use tokio1_crate::io;
-
-    use super::AssertUnmoved;
-
-    impl<R: io::AsyncRead> io::AsyncRead for AssertUnmoved<R> {
-        #[track_caller]
-        fn poll_read(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            buf: &mut io::ReadBuf<'_>,
-        ) -> Poll<io::Result<()>> {
-            self.get_pin_mut().poll_read(cx, buf)
-        }
+impl<T: ?Sized + Unpin> ops::DerefMut for AssertUnmoved<T> {
+    /// Gets a mutable reference to the underlying type.
+    ///
+    /// This is equivalent to [`get_mut`](AssertUnmoved::get_mut) method, and
+    /// only implemented for `T: Unpin`, like [`AsMut`] impl.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `AssertUnmoved` moved after being pinned and mutably accessed.
+    #[track_caller]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.get_mut()
     }
+}
 
-    impl<W: io::AsyncWrite> io::AsyncWrite for AssertUnmoved<W> {
-        #[track_caller]
-        fn poll_write(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            buf: &[u8],
-        ) -> Poll<io::Result<usize>> {
-            self.get_pin_mut().poll_write(cx, buf)
-        }
-
-        #[track_caller]
-        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-            self.get_pin_mut().poll_flush(cx)
-        }
-
-        #[track_caller]
-        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-            self.get_pin_mut().poll_shutdown(cx)
-        }
+impl<T: ?Sized + Unpin> AsMut<T> for AssertUnmoved<T> {
+    /// Gets a mutable reference to the underlying type.
+    ///
+    /// Unlike `AsRef`, this is only implemented for `T: Unpin`. For other
+    /// types, use [`get_mut`](AssertUnmoved::get_mut) method before pinned, or
+    /// [`get_pin_mut`](AssertUnmoved::get_pin_mut) method after pinned, so
+    /// that the intent is explicit at the call site.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `AssertUnmoved` moved after being pinned and mutably accessed.
+    #[track_caller]
+    fn as_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
 
-        #[track_caller]
-        fn poll_write_vectored(
-            self: Pin<&mut Self>,
-            cx: &mut Context<'_>,
-            bufs: &[std::io::IoSlice<'_>],
-        ) -> Poll<Result<usize, io::Error>> {
-            self.get_pin_mut().poll_write_vectored(cx, bufs)
+impl<T: Clone> Clone for AssertUnmoved<T> {
+    /// Returns a new `AssertUnmoved` with a clone of the underlying value.
+    ///
+    /// The clone lives at a different address than the original, so it starts
+    /// with fresh tracking, i.e., it has never been pinned and mutably accessed
+    /// even if the original has. The original is not affected. The tag, if
+    /// any, is kept.
+    fn clone(&self) -> Self {
+        match self.tag {
+            Some(tag) => Self::with_tag(self.inner.clone(), tag),
+            None => Self::new(self.inner.clone()),
         }
+    }
+}
 
-        fn is_write_vectored(&self) -> bool {
-            self.get_ref().is_write_vectored()
-        }
+impl<T> From<T> for AssertUnmoved<T> {
+    /// Converts a `T` into a `AssertUnmoved<T>`.
+    ///
+    /// This is equivalent to [`AssertUnmoved::new`].
+    fn from(inner: T) -> Self {
+        Self::new(inner)
     }
+}
 
-    impl<S: io::AsyncSeek> io::AsyncSeek for AssertUnmoved<S> {
-        #[track_caller]
-        fn start_seek(self: Pin<&mut Self>, pos: io::SeekFrom) -> io::Result<()> {
-            self.get_pin_mut().start_seek(pos)
-        }
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<T> From<AssertUnmoved<T>> for Arc<T> {
+    /// Converts a `AssertUnmoved<T>` into a `Arc<T>`.
+    ///
+    /// This is equivalent to [`Arc::new(this.into_inner())`](AssertUnmoved::into_inner).
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `AssertUnmoved` has been pinned and mutably accessed.
+    #[track_caller]
+    fn from(this: AssertUnmoved<T>) -> Self {
+        Self::new(this.into_inner())
+    }
+}
 
-        #[track_caller]
-        fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
-            self.get_pin_mut().poll_complete(cx)
-        }
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<T> From<AssertUnmoved<T>> for Rc<T> {
+    /// Converts a `AssertUnmoved<T>` into a `Rc<T>`.
+    ///
+    /// This is equivalent to [`Rc::new(this.into_inner())`](AssertUnmoved::into_inner).
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `AssertUnmoved` has been pinned and mutably accessed.
+    #[track_caller]
+    fn from(this: AssertUnmoved<T>) -> Self {
+        Self::new(this.into_inner())
     }
+}
 
-    impl<R: io::AsyncBufRead> io::AsyncBufRead for AssertUnmoved<R> {
-        #[track_caller]
-        fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
-            self.get_pin_mut().poll_fill_buf(cx)
-        }
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<T: Unpin> From<Pin<Box<T>>> for Pin<Box<AssertUnmoved<T>>> {
+    /// Wraps an existing pinned heap value in a new pinned `AssertUnmoved`.
+    ///
+    /// This moves `T` out of the given box into a newly allocated
+    /// `AssertUnmoved`, so this is only implemented for `T: Unpin`: moving a
+    /// `!Unpin` value out of `Pin<Box<T>>` would violate the pinning guarantee
+    /// that `AssertUnmoved` is meant to check. The returned `AssertUnmoved`
+    /// starts with fresh tracking, i.e., it has never been pinned and mutably
+    /// accessed.
+    fn from(pinned: Pin<Box<T>>) -> Self {
+        Box::pin(AssertUnmoved::new(*Pin::into_inner(pinned)))
+    }
+}
 
-        #[track_caller]
-        fn consume(self: Pin<&mut Self>, amt: usize) {
-            self.get_pin_mut().consume(amt);
-        }
+impl<T: Default> Default for AssertUnmoved<T> {
+    /// Creates a new `AssertUnmoved`, with the default value for `T`.
+    ///
+    /// This is equivalent to [`AssertUnmoved::new(T::default())`](AssertUnmoved::new).
+    /// Unlike `new`, this cannot be used in const contexts because
+    /// `Default::default` is not a `const fn`.
+    fn default() -> Self {
+        Self::new(T::default())
     }
 }
+
+forward_impls!(AssertUnmoved);
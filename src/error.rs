@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use core::{fmt, panic::Location};
+
+/// An error returned when an [`AssertUnmoved`](crate::AssertUnmoved) moved
+/// after being pinned and mutably accessed.
+///
+/// This is returned by [`AssertUnmoved::try_get_pin_mut`](crate::AssertUnmoved::try_get_pin_mut),
+/// and its [`Display`](fmt::Display) impl is used for panic messages.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct MoveError {
+    /// The address of the `AssertUnmoved` when it was first pinned and mutably accessed.
+    pub original_addr: usize,
+    /// The address of the `AssertUnmoved` when the move was detected.
+    pub current_addr: usize,
+    /// The location where the `AssertUnmoved` was first pinned and mutably accessed.
+    pub first_pinned_at: &'static Location<'static>,
+    /// The location where the move was detected, if known.
+    ///
+    /// This is `None` if the move was detected on drop.
+    pub detected_at: Option<&'static Location<'static>>,
+    /// The tag of the `AssertUnmoved`, if it was created with
+    /// [`AssertUnmoved::with_tag`](crate::AssertUnmoved::with_tag).
+    pub tag: Option<&'static str>,
+    pub(crate) kind: MoveErrorKind,
+    pub(crate) method: Option<&'static str>,
+}
+
+impl MoveError {
+    /// Returns when the move was detected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{future, pin::Pin};
+    ///
+    /// use assert_unmoved::{AssertUnmoved, MoveErrorKind};
+    ///
+    /// let mut future = AssertUnmoved::new(future::pending::<()>());
+    /// let _ = unsafe { Pin::new_unchecked(&mut future) }.get_pin_mut();
+    /// let mut future = Box::new(future);
+    /// let err = unsafe { Pin::new_unchecked(&mut *future) }.try_get_pin_mut().unwrap_err();
+    /// assert_eq!(err.kind(), MoveErrorKind::BetweenGetPinMutCalls);
+    /// ```
+    #[must_use]
+    pub const fn kind(&self) -> MoveErrorKind {
+        self.kind
+    }
+
+    /// Returns the method (e.g., `get_mut` or a trait method such as
+    /// `Sink::poll_ready`) that detected the move, if known.
+    #[must_use]
+    pub const fn method(&self) -> Option<&'static str> {
+        self.method
+    }
+}
+
+/// The kind of a [`MoveError`], returned by [`MoveError::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MoveErrorKind {
+    /// The `AssertUnmoved` moved between two pinned mutable accesses, such as
+    /// calls to [`get_pin_mut`](crate::AssertUnmoved::get_pin_mut).
+    BetweenGetPinMutCalls,
+    /// The `AssertUnmoved` or its underlying value moved after being pinned
+    /// and mutably accessed, and this was detected by a method that does not
+    /// require pinning, such as [`get_mut`](crate::AssertUnmoved::get_mut) or
+    /// [`into_inner`](crate::AssertUnmoved::into_inner).
+    AfterGetPinMutCall,
+    /// The `AssertUnmoved` moved after being pinned and mutably accessed, and
+    /// was then dropped.
+    BeforeDrop,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AssertUnmoved")?;
+        if let Some(tag) = self.tag {
+            write!(f, "[{}]", tag)?;
+        }
+        match self.kind {
+            MoveErrorKind::BetweenGetPinMutCalls => {
+                f.write_str(" moved between get_pin_mut calls")?;
+            }
+            MoveErrorKind::AfterGetPinMutCall => {
+                f.write_str(" moved after get_pin_mut call")?;
+            }
+            MoveErrorKind::BeforeDrop => write!(
+                f,
+                " moved before drop: was at {0:#x} ({0}), now at {1:#x} ({1})",
+                self.original_addr, self.current_addr
+            )?,
+        }
+        if let Some(method) = self.method {
+            write!(f, " (detected in {})", method)?;
+        }
+        write!(f, "\n\tfirst pinned mutably accessed at {}", self.first_pinned_at)?;
+        if let Some(detected_at) = self.detected_at {
+            write!(f, "\n\tdetected at {}", detected_at)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for MoveError {}
@@ -0,0 +1,1007 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Trait impls shared by `AssertUnmoved` and `NoCheck`, so that the two types
+// keep the same API. They only forward to `get_ref`, `get_mut`, and
+// `get_pin_mut`, which check for moves in `AssertUnmoved` and do nothing in
+// `NoCheck`.
+//
+// Impls with type-specific behavior or documentation (e.g., `Clone`,
+// `DerefMut`, and `From`) are written in each type's module.
+//
+// Note on `&mut self` methods: methods of traits such as `Iterator`,
+// `std::io::Read`, and `tower_service::Service` take `&mut self`, not a pinned
+// receiver, so their impls go through `get_mut`. `get_mut` only detects moves
+// after an `AssertUnmoved` has been pinned and mutably accessed (e.g., by
+// another trait impl with a pinned receiver).
+macro_rules! forward_impls {
+    ($ty:ident) => {
+        mod core_traits {
+            use core::{
+                cmp::Ordering,
+                fmt,
+                future::Future,
+                hash::{Hash, Hasher},
+                iter::FusedIterator,
+                ops,
+                pin::Pin,
+                task::{Context, Poll},
+            };
+
+            use super::$ty;
+
+            impl<T: ?Sized> ops::Deref for $ty<T> {
+                type Target = T;
+
+                fn deref(&self) -> &Self::Target {
+                    self.get_ref()
+                }
+            }
+
+            impl<T: ?Sized> AsRef<T> for $ty<T> {
+                fn as_ref(&self) -> &T {
+                    self.get_ref()
+                }
+            }
+
+            impl<T: ?Sized + fmt::Display> fmt::Display for $ty<T> {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Display::fmt(self.get_ref(), f)
+                }
+            }
+
+            // Comparisons and hashing only look at the underlying values; the address and
+            // location used for tracking are not part of the logical value.
+            impl<T: ?Sized + PartialEq> PartialEq for $ty<T> {
+                fn eq(&self, other: &Self) -> bool {
+                    self.get_ref() == other.get_ref()
+                }
+            }
+
+            impl<T: ?Sized + Eq> Eq for $ty<T> {}
+
+            impl<T: ?Sized + PartialOrd> PartialOrd for $ty<T> {
+                fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                    self.get_ref().partial_cmp(other.get_ref())
+                }
+            }
+
+            impl<T: ?Sized + Ord> Ord for $ty<T> {
+                fn cmp(&self, other: &Self) -> Ordering {
+                    self.get_ref().cmp(other.get_ref())
+                }
+            }
+
+            impl<T: ?Sized + Hash> Hash for $ty<T> {
+                fn hash<H: Hasher>(&self, state: &mut H) {
+                    self.get_ref().hash(state);
+                }
+            }
+
+            impl<T: ?Sized + ops::RangeBounds<U>, U: ?Sized> ops::RangeBounds<U> for $ty<T> {
+                fn start_bound(&self) -> ops::Bound<&U> {
+                    self.get_ref().start_bound()
+                }
+
+                fn end_bound(&self) -> ops::Bound<&U> {
+                    self.get_ref().end_bound()
+                }
+            }
+
+            impl<I: ?Sized + Iterator> Iterator for $ty<I> {
+                type Item = I::Item;
+
+                #[track_caller]
+                fn next(&mut self) -> Option<Self::Item> {
+                    self.get_mut().next()
+                }
+
+                fn size_hint(&self) -> (usize, Option<usize>) {
+                    self.get_ref().size_hint()
+                }
+            }
+
+            impl<I: ?Sized + DoubleEndedIterator> DoubleEndedIterator for $ty<I> {
+                #[track_caller]
+                fn next_back(&mut self) -> Option<Self::Item> {
+                    self.get_mut().next_back()
+                }
+            }
+
+            impl<I: ?Sized + ExactSizeIterator> ExactSizeIterator for $ty<I> {
+                fn len(&self) -> usize {
+                    self.get_ref().len()
+                }
+            }
+
+            impl<I: ?Sized + FusedIterator> FusedIterator for $ty<I> {}
+
+            impl<W: ?Sized + fmt::Write> fmt::Write for $ty<W> {
+                #[track_caller]
+                fn write_str(&mut self, s: &str) -> fmt::Result {
+                    self.get_mut().write_str(s)
+                }
+
+                #[track_caller]
+                fn write_char(&mut self, c: char) -> fmt::Result {
+                    self.get_mut().write_char(c)
+                }
+
+                #[track_caller]
+                fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+                    self.get_mut().write_fmt(args)
+                }
+            }
+
+            impl<F: ?Sized + Future> Future for $ty<F> {
+                type Output = F::Output;
+
+                #[track_caller]
+                fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                    self.get_pin_mut().poll(cx)
+                }
+            }
+        }
+
+        #[cfg(feature = "std")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+        mod std_io {
+            use std::{io, string::String, vec::Vec};
+
+            use super::$ty;
+
+            impl<R: ?Sized + io::Read> io::Read for $ty<R> {
+                #[track_caller]
+                fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                    self.get_mut().read(buf)
+                }
+
+                #[track_caller]
+                fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+                    self.get_mut().read_vectored(bufs)
+                }
+
+                #[track_caller]
+                fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+                    self.get_mut().read_to_end(buf)
+                }
+
+                #[track_caller]
+                fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+                    self.get_mut().read_to_string(buf)
+                }
+
+                #[track_caller]
+                fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+                    self.get_mut().read_exact(buf)
+                }
+            }
+
+            impl<W: ?Sized + io::Write> io::Write for $ty<W> {
+                #[track_caller]
+                fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                    self.get_mut().write(buf)
+                }
+
+                #[track_caller]
+                fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+                    self.get_mut().write_vectored(bufs)
+                }
+
+                #[track_caller]
+                fn flush(&mut self) -> io::Result<()> {
+                    self.get_mut().flush()
+                }
+
+                #[track_caller]
+                fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+                    self.get_mut().write_all(buf)
+                }
+            }
+
+            impl<S: ?Sized + io::Seek> io::Seek for $ty<S> {
+                #[track_caller]
+                fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+                    self.get_mut().seek(pos)
+                }
+            }
+
+            impl<R: ?Sized + io::BufRead> io::BufRead for $ty<R> {
+                #[track_caller]
+                fn fill_buf(&mut self) -> io::Result<&[u8]> {
+                    self.get_mut().fill_buf()
+                }
+
+                #[track_caller]
+                fn consume(&mut self, amt: usize) {
+                    self.get_mut().consume(amt);
+                }
+
+                #[track_caller]
+                fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> io::Result<usize> {
+                    self.get_mut().read_until(byte, buf)
+                }
+
+                #[track_caller]
+                fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+                    self.get_mut().read_line(buf)
+                }
+            }
+        }
+
+        #[cfg(feature = "futures-core03")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "futures-core03")))]
+        mod futures_core03 {
+            use core::{
+                pin::Pin,
+                task::{Context, Poll},
+            };
+
+            use futures_core::{
+                future::FusedFuture,
+                stream::{FusedStream, Stream},
+            };
+            use futures_sink::Sink;
+
+            use super::$ty;
+
+            impl<F: ?Sized + FusedFuture> FusedFuture for $ty<F> {
+                fn is_terminated(&self) -> bool {
+                    self.get_ref().is_terminated()
+                }
+            }
+
+            impl<S: ?Sized + Stream> Stream for $ty<S> {
+                type Item = S::Item;
+
+                #[track_caller]
+                fn poll_next(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<Option<Self::Item>> {
+                    self.get_pin_mut().poll_next(cx)
+                }
+
+                fn size_hint(&self) -> (usize, Option<usize>) {
+                    self.get_ref().size_hint()
+                }
+            }
+
+            impl<S: ?Sized + FusedStream> FusedStream for $ty<S> {
+                fn is_terminated(&self) -> bool {
+                    self.get_ref().is_terminated()
+                }
+            }
+
+            impl<S: ?Sized + Sink<Item>, Item> Sink<Item> for $ty<S> {
+                type Error = S::Error;
+
+                #[track_caller]
+                fn poll_ready(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<Result<(), Self::Error>> {
+                    self.get_pin_mut_in("Sink::poll_ready").poll_ready(cx)
+                }
+
+                #[track_caller]
+                fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+                    self.get_pin_mut_in("Sink::start_send").start_send(item)
+                }
+
+                #[track_caller]
+                fn poll_flush(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<Result<(), Self::Error>> {
+                    self.get_pin_mut_in("Sink::poll_flush").poll_flush(cx)
+                }
+
+                #[track_caller]
+                fn poll_close(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<Result<(), Self::Error>> {
+                    self.get_pin_mut_in("Sink::poll_close").poll_close(cx)
+                }
+            }
+        }
+
+        #[cfg(feature = "futures03")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "futures03")))]
+        mod futures03 {
+            use core::{
+                pin::Pin,
+                task::{Context, Poll},
+            };
+
+            use futures_io as io;
+
+            use super::$ty;
+
+            impl<R: ?Sized + io::AsyncRead> io::AsyncRead for $ty<R> {
+                #[track_caller]
+                fn poll_read(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    buf: &mut [u8],
+                ) -> Poll<io::Result<usize>> {
+                    self.get_pin_mut().poll_read(cx, buf)
+                }
+
+                #[track_caller]
+                fn poll_read_vectored(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    bufs: &mut [io::IoSliceMut<'_>],
+                ) -> Poll<io::Result<usize>> {
+                    self.get_pin_mut().poll_read_vectored(cx, bufs)
+                }
+            }
+
+            impl<W: ?Sized + io::AsyncWrite> io::AsyncWrite for $ty<W> {
+                #[track_caller]
+                fn poll_write(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    buf: &[u8],
+                ) -> Poll<io::Result<usize>> {
+                    self.get_pin_mut().poll_write(cx, buf)
+                }
+
+                #[track_caller]
+                fn poll_write_vectored(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    bufs: &[io::IoSlice<'_>],
+                ) -> Poll<io::Result<usize>> {
+                    self.get_pin_mut().poll_write_vectored(cx, bufs)
+                }
+
+                #[track_caller]
+                fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+                    self.get_pin_mut().poll_flush(cx)
+                }
+
+                #[track_caller]
+                fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+                    self.get_pin_mut().poll_close(cx)
+                }
+            }
+
+            impl<S: ?Sized + io::AsyncSeek> io::AsyncSeek for $ty<S> {
+                #[track_caller]
+                fn poll_seek(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    pos: io::SeekFrom,
+                ) -> Poll<io::Result<u64>> {
+                    self.get_pin_mut().poll_seek(cx, pos)
+                }
+            }
+
+            impl<R: ?Sized + io::AsyncBufRead> io::AsyncBufRead for $ty<R> {
+                #[track_caller]
+                fn poll_fill_buf(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<io::Result<&[u8]>> {
+                    self.get_pin_mut().poll_fill_buf(cx)
+                }
+
+                #[track_caller]
+                fn consume(self: Pin<&mut Self>, amt: usize) {
+                    self.get_pin_mut().consume(amt);
+                }
+            }
+        }
+
+        #[cfg(feature = "tokio02")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "tokio02")))]
+        mod tokio02 {
+            use core::{
+                mem::MaybeUninit,
+                pin::Pin,
+                task::{Context, Poll},
+            };
+            use std::io;
+
+            use bytes05::{Buf, BufMut};
+            use tokio02_crate::io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite};
+
+            use super::$ty;
+
+            impl<R: AsyncRead> AsyncRead for $ty<R> {
+                unsafe fn prepare_uninitialized_buffer(&self, buf: &mut [MaybeUninit<u8>]) -> bool {
+                    // SAFETY: The safety contract must be upheld by the caller.
+                    //
+                    // Forwarding to the underlying reader is sound because `poll_read`
+                    // below passes the buffer to the underlying reader as is. If the
+                    // underlying reader returns `true`, it has zeroed the whole buffer.
+                    // If it returns `false`, it guarantees that its `poll_read` never
+                    // reads from parts of the buffer it did not write to, and the same
+                    // guarantee then holds for the wrapper.
+                    unsafe { self.get_ref().prepare_uninitialized_buffer(buf) }
+                }
+
+                #[track_caller]
+                fn poll_read(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    buf: &mut [u8],
+                ) -> Poll<io::Result<usize>> {
+                    self.get_pin_mut().poll_read(cx, buf)
+                }
+
+                #[track_caller]
+                fn poll_read_buf<B: BufMut>(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    buf: &mut B,
+                ) -> Poll<io::Result<usize>>
+                where
+                    Self: Sized,
+                {
+                    self.get_pin_mut().poll_read_buf(cx, buf)
+                }
+            }
+
+            impl<W: AsyncWrite> AsyncWrite for $ty<W> {
+                #[track_caller]
+                fn poll_write(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    buf: &[u8],
+                ) -> Poll<io::Result<usize>> {
+                    self.get_pin_mut().poll_write(cx, buf)
+                }
+
+                #[track_caller]
+                fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+                    self.get_pin_mut().poll_flush(cx)
+                }
+
+                #[track_caller]
+                fn poll_shutdown(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<io::Result<()>> {
+                    self.get_pin_mut().poll_shutdown(cx)
+                }
+
+                #[track_caller]
+                fn poll_write_buf<B: Buf>(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    buf: &mut B,
+                ) -> Poll<Result<usize, io::Error>>
+                where
+                    Self: Sized,
+                {
+                    self.get_pin_mut().poll_write_buf(cx, buf)
+                }
+            }
+
+            impl<S: ?Sized + AsyncSeek> AsyncSeek for $ty<S> {
+                #[track_caller]
+                fn start_seek(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    pos: io::SeekFrom,
+                ) -> Poll<io::Result<()>> {
+                    self.get_pin_mut().start_seek(cx, pos)
+                }
+
+                #[track_caller]
+                fn poll_complete(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<io::Result<u64>> {
+                    self.get_pin_mut().poll_complete(cx)
+                }
+            }
+
+            impl<R: AsyncBufRead> AsyncBufRead for $ty<R> {
+                #[track_caller]
+                fn poll_fill_buf(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<io::Result<&[u8]>> {
+                    self.get_pin_mut().poll_fill_buf(cx)
+                }
+
+                #[track_caller]
+                fn consume(self: Pin<&mut Self>, amt: usize) {
+                    self.get_pin_mut().consume(amt);
+                }
+            }
+        }
+
+        #[cfg(feature = "tokio03")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "tokio03")))]
+        mod tokio03 {
+            use core::{
+                pin::Pin,
+                task::{Context, Poll},
+            };
+
+            use tokio03_crate::io;
+
+            use super::$ty;
+
+            impl<R: ?Sized + io::AsyncRead> io::AsyncRead for $ty<R> {
+                #[track_caller]
+                fn poll_read(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    buf: &mut io::ReadBuf<'_>,
+                ) -> Poll<io::Result<()>> {
+                    self.get_pin_mut().poll_read(cx, buf)
+                }
+            }
+
+            impl<W: ?Sized + io::AsyncWrite> io::AsyncWrite for $ty<W> {
+                #[track_caller]
+                fn poll_write(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    buf: &[u8],
+                ) -> Poll<io::Result<usize>> {
+                    self.get_pin_mut().poll_write(cx, buf)
+                }
+
+                #[track_caller]
+                fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+                    self.get_pin_mut().poll_flush(cx)
+                }
+
+                #[track_caller]
+                fn poll_shutdown(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<io::Result<()>> {
+                    self.get_pin_mut().poll_shutdown(cx)
+                }
+
+                #[track_caller]
+                fn poll_write_vectored(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    bufs: &[std::io::IoSlice<'_>],
+                ) -> Poll<Result<usize, io::Error>> {
+                    self.get_pin_mut().poll_write_vectored(cx, bufs)
+                }
+
+                fn is_write_vectored(&self) -> bool {
+                    self.get_ref().is_write_vectored()
+                }
+            }
+
+            impl<S: ?Sized + io::AsyncSeek> io::AsyncSeek for $ty<S> {
+                #[track_caller]
+                fn start_seek(self: Pin<&mut Self>, pos: io::SeekFrom) -> io::Result<()> {
+                    self.get_pin_mut().start_seek(pos)
+                }
+
+                #[track_caller]
+                fn poll_complete(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<io::Result<u64>> {
+                    self.get_pin_mut().poll_complete(cx)
+                }
+            }
+
+            impl<R: ?Sized + io::AsyncBufRead> io::AsyncBufRead for $ty<R> {
+                #[track_caller]
+                fn poll_fill_buf(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<io::Result<&[u8]>> {
+                    self.get_pin_mut().poll_fill_buf(cx)
+                }
+
+                #[track_caller]
+                fn consume(self: Pin<&mut Self>, amt: usize) {
+                    self.get_pin_mut().consume(amt);
+                }
+            }
+        }
+
+        #[cfg(feature = "tokio1")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "tokio1")))]
+        mod tokio1 {
+            use core::{
+                pin::Pin,
+                task::{Context, Poll},
+            };
+
+            use tokio1_crate::io;
+
+            use super::$ty;
+
+            impl<R: ?Sized + io::AsyncRead> io::AsyncRead for $ty<R> {
+                /// Note that the filled cursor of [`ReadBuf`](io::ReadBuf) is managed by
+                /// the underlying reader, not by the wrapper. Partially filled
+                /// buffers are passed through as is.
+                #[track_caller]
+                fn poll_read(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    buf: &mut io::ReadBuf<'_>,
+                ) -> Poll<io::Result<()>> {
+                    self.get_pin_mut().poll_read(cx, buf)
+                }
+            }
+
+            impl<W: ?Sized + io::AsyncWrite> io::AsyncWrite for $ty<W> {
+                #[track_caller]
+                fn poll_write(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    buf: &[u8],
+                ) -> Poll<io::Result<usize>> {
+                    self.get_pin_mut().poll_write(cx, buf)
+                }
+
+                #[track_caller]
+                fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+                    self.get_pin_mut().poll_flush(cx)
+                }
+
+                #[track_caller]
+                fn poll_shutdown(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<io::Result<()>> {
+                    self.get_pin_mut().poll_shutdown(cx)
+                }
+
+                #[track_caller]
+                fn poll_write_vectored(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                    bufs: &[std::io::IoSlice<'_>],
+                ) -> Poll<Result<usize, io::Error>> {
+                    self.get_pin_mut().poll_write_vectored(cx, bufs)
+                }
+
+                fn is_write_vectored(&self) -> bool {
+                    self.get_ref().is_write_vectored()
+                }
+            }
+
+            impl<S: ?Sized + io::AsyncSeek> io::AsyncSeek for $ty<S> {
+                #[track_caller]
+                fn start_seek(self: Pin<&mut Self>, pos: io::SeekFrom) -> io::Result<()> {
+                    self.get_pin_mut().start_seek(pos)
+                }
+
+                #[track_caller]
+                fn poll_complete(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<io::Result<u64>> {
+                    self.get_pin_mut().poll_complete(cx)
+                }
+            }
+
+            impl<R: ?Sized + io::AsyncBufRead> io::AsyncBufRead for $ty<R> {
+                #[track_caller]
+                fn poll_fill_buf(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<io::Result<&[u8]>> {
+                    self.get_pin_mut().poll_fill_buf(cx)
+                }
+
+                #[track_caller]
+                fn consume(self: Pin<&mut Self>, amt: usize) {
+                    self.get_pin_mut().consume(amt);
+                }
+            }
+        }
+
+        #[cfg(feature = "tower-service03")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "tower-service03")))]
+        mod tower_service03 {
+            use core::task::{Context, Poll};
+
+            use tower_service::Service;
+
+            use super::$ty;
+
+            // See the note on `&mut self` methods at the top of this file.
+            impl<S: ?Sized + Service<Request>, Request> Service<Request> for $ty<S> {
+                type Response = S::Response;
+                type Error = S::Error;
+                type Future = S::Future;
+
+                #[track_caller]
+                fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+                    self.get_mut().poll_ready(cx)
+                }
+
+                #[track_caller]
+                fn call(&mut self, req: Request) -> Self::Future {
+                    self.get_mut().call(req)
+                }
+            }
+        }
+
+        #[cfg(feature = "http-body1")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "http-body1")))]
+        mod http_body1 {
+            use core::{
+                pin::Pin,
+                task::{Context, Poll},
+            };
+
+            use http_body::{Body, Frame, SizeHint};
+
+            use super::$ty;
+
+            impl<B: ?Sized + Body> Body for $ty<B> {
+                type Data = B::Data;
+                type Error = B::Error;
+
+                #[track_caller]
+                fn poll_frame(
+                    self: Pin<&mut Self>,
+                    cx: &mut Context<'_>,
+                ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+                    self.get_pin_mut().poll_frame(cx)
+                }
+
+                fn is_end_stream(&self) -> bool {
+                    self.get_ref().is_end_stream()
+                }
+
+                fn size_hint(&self) -> SizeHint {
+                    self.get_ref().size_hint()
+                }
+            }
+        }
+
+        #[cfg(feature = "embedded-hal-async1")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "embedded-hal-async1")))]
+        mod embedded_hal_async1 {
+            use core::future::Future;
+
+            use embedded_hal_async::{i2c, spi};
+
+            use super::$ty;
+
+            // See the note on `&mut self` methods at the top of this file. These
+            // methods are not `async fn` so that `#[track_caller]` works.
+
+            impl<T: ?Sized + spi::ErrorType> spi::ErrorType for $ty<T> {
+                type Error = T::Error;
+            }
+
+            impl<T, Word> spi::SpiBus<Word> for $ty<T>
+            where
+                T: ?Sized + spi::SpiBus<Word>,
+                Word: 'static + Copy,
+            {
+                #[track_caller]
+                fn read(
+                    &mut self,
+                    words: &mut [Word],
+                ) -> impl Future<Output = Result<(), Self::Error>> {
+                    self.get_mut().read(words)
+                }
+
+                #[track_caller]
+                fn write(
+                    &mut self,
+                    words: &[Word],
+                ) -> impl Future<Output = Result<(), Self::Error>> {
+                    self.get_mut().write(words)
+                }
+
+                #[track_caller]
+                fn transfer(
+                    &mut self,
+                    read: &mut [Word],
+                    write: &[Word],
+                ) -> impl Future<Output = Result<(), Self::Error>> {
+                    self.get_mut().transfer(read, write)
+                }
+
+                #[track_caller]
+                fn transfer_in_place(
+                    &mut self,
+                    words: &mut [Word],
+                ) -> impl Future<Output = Result<(), Self::Error>> {
+                    self.get_mut().transfer_in_place(words)
+                }
+
+                #[track_caller]
+                fn flush(&mut self) -> impl Future<Output = Result<(), Self::Error>> {
+                    self.get_mut().flush()
+                }
+            }
+
+            impl<T: ?Sized + i2c::ErrorType> i2c::ErrorType for $ty<T> {
+                type Error = T::Error;
+            }
+
+            impl<T: ?Sized + i2c::I2c<A>, A: i2c::AddressMode> i2c::I2c<A> for $ty<T> {
+                #[track_caller]
+                fn read(
+                    &mut self,
+                    address: A,
+                    read: &mut [u8],
+                ) -> impl Future<Output = Result<(), Self::Error>> {
+                    self.get_mut().read(address, read)
+                }
+
+                #[track_caller]
+                fn write(
+                    &mut self,
+                    address: A,
+                    write: &[u8],
+                ) -> impl Future<Output = Result<(), Self::Error>> {
+                    self.get_mut().write(address, write)
+                }
+
+                #[track_caller]
+                fn write_read(
+                    &mut self,
+                    address: A,
+                    write: &[u8],
+                    read: &mut [u8],
+                ) -> impl Future<Output = Result<(), Self::Error>> {
+                    self.get_mut().write_read(address, write, read)
+                }
+
+                #[track_caller]
+                fn transaction(
+                    &mut self,
+                    address: A,
+                    operations: &mut [i2c::Operation<'_>],
+                ) -> impl Future<Output = Result<(), Self::Error>> {
+                    self.get_mut().transaction(address, operations)
+                }
+            }
+        }
+
+        #[cfg(feature = "serde1")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "serde1")))]
+        mod serde1 {
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+            use super::$ty;
+
+            // Only the underlying value is serialized; the address and location used
+            // for tracking are runtime state.
+
+            impl<T: ?Sized + Serialize> Serialize for $ty<T> {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    self.get_ref().serialize(serializer)
+                }
+            }
+
+            impl<'de, T: Deserialize<'de>> Deserialize<'de> for $ty<T> {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    T::deserialize(deserializer).map(Self::new)
+                }
+            }
+        }
+
+        #[cfg(feature = "tokio-util07")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "tokio-util07")))]
+        mod tokio_util07 {
+            use bytes1_crate::BytesMut;
+            use tokio_util07_crate::codec::{Decoder, Encoder};
+
+            use super::$ty;
+
+            // See the note on `&mut self` methods at the top of this file.
+
+            impl<E: ?Sized + Encoder<Item>, Item> Encoder<Item> for $ty<E> {
+                type Error = E::Error;
+
+                #[track_caller]
+                fn encode(&mut self, item: Item, dst: &mut BytesMut) -> Result<(), Self::Error> {
+                    self.get_mut().encode(item, dst)
+                }
+            }
+
+            impl<D: ?Sized + Decoder> Decoder for $ty<D> {
+                type Item = D::Item;
+                type Error = D::Error;
+
+                #[track_caller]
+                fn decode(
+                    &mut self,
+                    src: &mut BytesMut,
+                ) -> Result<Option<Self::Item>, Self::Error> {
+                    self.get_mut().decode(src)
+                }
+
+                #[track_caller]
+                fn decode_eof(
+                    &mut self,
+                    buf: &mut BytesMut,
+                ) -> Result<Option<Self::Item>, Self::Error> {
+                    self.get_mut().decode_eof(buf)
+                }
+            }
+        }
+
+        #[cfg(feature = "bytes1")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "bytes1")))]
+        mod bytes1 {
+            use bytes1_crate::{buf::UninitSlice, Buf, BufMut, Bytes};
+
+            use super::$ty;
+
+            // Methods taking `&self` go through `get_ref`. For the others, see the
+            // note on `&mut self` methods at the top of this file.
+
+            impl<B: ?Sized + Buf> Buf for $ty<B> {
+                fn remaining(&self) -> usize {
+                    self.get_ref().remaining()
+                }
+
+                fn chunk(&self) -> &[u8] {
+                    self.get_ref().chunk()
+                }
+
+                #[track_caller]
+                fn advance(&mut self, cnt: usize) {
+                    self.get_mut().advance(cnt);
+                }
+
+                #[track_caller]
+                fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+                    self.get_mut().copy_to_bytes(len)
+                }
+            }
+
+            // SAFETY: All methods forward to the underlying `BufMut` as is, so the
+            // guarantees of the underlying implementation hold for the wrapper.
+            unsafe impl<B: ?Sized + BufMut> BufMut for $ty<B> {
+                fn remaining_mut(&self) -> usize {
+                    self.get_ref().remaining_mut()
+                }
+
+                #[track_caller]
+                unsafe fn advance_mut(&mut self, cnt: usize) {
+                    // SAFETY: The safety contract must be upheld by the caller.
+                    unsafe { self.get_mut().advance_mut(cnt) }
+                }
+
+                #[track_caller]
+                fn chunk_mut(&mut self) -> &mut UninitSlice {
+                    self.get_mut().chunk_mut()
+                }
+            }
+        }
+
+        #[cfg(assert_unmoved_unstable)]
+        #[cfg_attr(docsrs, doc(cfg(assert_unmoved_unstable)))]
+        mod unstable {
+            use core::{
+                ops::{Coroutine, CoroutineState},
+                pin::Pin,
+            };
+
+            use super::$ty;
+
+            impl<G: ?Sized + Coroutine<R>, R> Coroutine<R> for $ty<G> {
+                type Yield = G::Yield;
+                type Return = G::Return;
+
+                #[track_caller]
+                fn resume(
+                    self: Pin<&mut Self>,
+                    arg: R,
+                ) -> CoroutineState<Self::Yield, Self::Return> {
+                    self.get_pin_mut().resume(arg)
+                }
+            }
+        }
+    };
+}
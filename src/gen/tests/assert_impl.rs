@@ -66,4 +66,25 @@ const _: fn() = || {
     assert_not_ref_unwind_safe!(
         crate::assert_unmoved::AssertUnmoved<NotRefUnwindSafe>
     );
+    assert_send::<crate::error::MoveError>();
+    assert_sync::<crate::error::MoveError>();
+    assert_unpin::<crate::error::MoveError>();
+    assert_unwind_safe::<crate::error::MoveError>();
+    assert_ref_unwind_safe::<crate::error::MoveError>();
+    assert_send::<crate::error::MoveErrorKind>();
+    assert_sync::<crate::error::MoveErrorKind>();
+    assert_unpin::<crate::error::MoveErrorKind>();
+    assert_unwind_safe::<crate::error::MoveErrorKind>();
+    assert_ref_unwind_safe::<crate::error::MoveErrorKind>();
+    assert_send::<crate::no_check::NoCheck<()>>();
+    assert_send::<crate::no_check::NoCheck<NotSync>>();
+    assert_not_send!(crate::no_check::NoCheck<NotSend>);
+    assert_sync::<crate::no_check::NoCheck<()>>();
+    assert_sync::<crate::no_check::NoCheck<NotSend>>();
+    assert_not_sync!(crate::no_check::NoCheck<NotSync>);
+    assert_not_unpin!(crate::no_check::NoCheck<()>);
+    assert_unwind_safe::<crate::no_check::NoCheck<()>>();
+    assert_not_unwind_safe!(crate::no_check::NoCheck<NotUnwindSafe>);
+    assert_ref_unwind_safe::<crate::no_check::NoCheck<()>>();
+    assert_not_ref_unwind_safe!(crate::no_check::NoCheck<NotRefUnwindSafe>);
 };
@@ -0,0 +1,1030 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// This file is @generated by assert-unmoved-internal-codegen
+// (gen_move_detection_tests function at tools/codegen/src/main.rs).
+// It is not intended for manual editing.
+
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(
+    clippy::let_underscore_future,
+    clippy::std_instead_of_alloc,
+    clippy::std_instead_of_core,
+    clippy::undocumented_unsafe_blocks,
+)]
+use core::{pin::Pin, task::{Context, Poll}};
+use std::boxed::Box;
+use crate::assert_unmoved::AssertUnmoved;
+struct Mock;
+/// Calls `f` with `AssertUnmoved` pinned on the stack, moves it to the heap,
+/// and then calls `f` again.
+fn assert_move_detected<T>(
+    inner: T,
+    mut f: impl FnMut(Pin<&mut AssertUnmoved<T>>, &mut Context<'_>),
+) {
+    let waker = futures::task::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut x = AssertUnmoved::new(inner);
+    f(unsafe { Pin::new_unchecked(&mut x) }, &mut cx);
+    let mut x = Box::new(x);
+    f(unsafe { Pin::new_unchecked(&mut *x) }, &mut cx);
+}
+/// Calls `f`, moves `AssertUnmoved` to the heap, and then calls `f` again.
+/// `AssertUnmoved` is not pinned, so moving it is fine.
+fn assert_move_allowed_before_pin<T>(
+    inner: T,
+    mut f: impl FnMut(&mut AssertUnmoved<T>),
+) {
+    let mut x = AssertUnmoved::new(inner);
+    f(&mut x);
+    let mut x = Box::new(x);
+    f(&mut x);
+}
+/// Pins `AssertUnmoved` on the stack and accesses it, moves it to the heap,
+/// and then calls `f`.
+fn assert_move_detected_after_pin<T>(inner: T, f: impl FnOnce(&mut AssertUnmoved<T>)) {
+    let mut x = AssertUnmoved::new(inner);
+    let _ = unsafe { Pin::new_unchecked(&mut x) }.get_pin_mut();
+    let mut x = Box::new(x);
+    f(&mut x);
+}
+impl core::future::Future for Mock {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        Poll::Pending
+    }
+}
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn future() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = core::future::Future::poll(x, cx);
+        },
+    );
+}
+#[cfg(feature = "futures-core03")]
+impl futures_core::Stream for Mock {
+    type Item = ();
+    fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<()>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "futures-core03")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn futures_core03_stream() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = futures_core::Stream::poll_next(x, cx);
+        },
+    );
+}
+#[cfg(feature = "futures-core03")]
+impl futures_sink::Sink<()> for Mock {
+    type Error = ();
+    fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        Poll::Pending
+    }
+    fn start_send(self: Pin<&mut Self>, (): ()) -> Result<(), ()> {
+        Ok(())
+    }
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        Poll::Pending
+    }
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "futures-core03")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn futures_core03_sink() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = futures_sink::Sink::<()>::poll_ready(x, cx);
+        },
+    );
+}
+#[cfg(feature = "futures03")]
+impl futures_io::AsyncRead for Mock {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &mut [u8],
+    ) -> Poll<futures_io::Result<usize>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "futures03")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn futures03_async_read() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = futures_io::AsyncRead::poll_read(x, cx, &mut [0; 1]);
+        },
+    );
+}
+#[cfg(feature = "futures03")]
+impl futures_io::AsyncWrite for Mock {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &[u8],
+    ) -> Poll<futures_io::Result<usize>> {
+        Poll::Pending
+    }
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<futures_io::Result<()>> {
+        Poll::Pending
+    }
+    fn poll_close(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<futures_io::Result<()>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "futures03")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn futures03_async_write() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = futures_io::AsyncWrite::poll_write(x, cx, &[0]);
+        },
+    );
+}
+#[cfg(feature = "futures03")]
+impl futures_io::AsyncSeek for Mock {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: futures_io::SeekFrom,
+    ) -> Poll<futures_io::Result<u64>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "futures03")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn futures03_async_seek() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = futures_io::AsyncSeek::poll_seek(
+                x,
+                cx,
+                futures_io::SeekFrom::Start(0),
+            );
+        },
+    );
+}
+#[cfg(feature = "futures03")]
+impl futures_io::AsyncBufRead for Mock {
+    fn poll_fill_buf(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<futures_io::Result<&[u8]>> {
+        Poll::Pending
+    }
+    fn consume(self: Pin<&mut Self>, _: usize) {}
+}
+#[cfg(feature = "futures03")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn futures03_async_buf_read() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = futures_io::AsyncBufRead::poll_fill_buf(x, cx);
+        },
+    );
+}
+#[cfg(feature = "tokio02")]
+impl tokio02_crate::io::AsyncRead for Mock {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "tokio02")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn tokio02_async_read() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = tokio02_crate::io::AsyncRead::poll_read(x, cx, &mut [0; 1]);
+        },
+    );
+}
+#[cfg(feature = "tokio02")]
+impl tokio02_crate::io::AsyncWrite for Mock {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Pending
+    }
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Poll::Pending
+    }
+    fn poll_shutdown(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "tokio02")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn tokio02_async_write() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = tokio02_crate::io::AsyncWrite::poll_write(x, cx, &[0]);
+        },
+    );
+}
+#[cfg(feature = "tokio02")]
+impl tokio02_crate::io::AsyncSeek for Mock {
+    fn start_seek(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: std::io::SeekFrom,
+    ) -> Poll<std::io::Result<()>> {
+        Poll::Pending
+    }
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<std::io::Result<u64>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "tokio02")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn tokio02_async_seek() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = tokio02_crate::io::AsyncSeek::start_seek(
+                x,
+                cx,
+                std::io::SeekFrom::Start(0),
+            );
+        },
+    );
+}
+#[cfg(feature = "tokio02")]
+impl tokio02_crate::io::AsyncBufRead for Mock {
+    fn poll_fill_buf(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<std::io::Result<&[u8]>> {
+        Poll::Pending
+    }
+    fn consume(self: Pin<&mut Self>, _: usize) {}
+}
+#[cfg(feature = "tokio02")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn tokio02_async_buf_read() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = tokio02_crate::io::AsyncBufRead::poll_fill_buf(x, cx);
+        },
+    );
+}
+#[cfg(feature = "tokio03")]
+impl tokio03_crate::io::AsyncRead for Mock {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &mut tokio03_crate::io::ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "tokio03")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn tokio03_async_read() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = tokio03_crate::io::AsyncRead::poll_read(
+                x,
+                cx,
+                &mut tokio03_crate::io::ReadBuf::new(&mut [0; 1]),
+            );
+        },
+    );
+}
+#[cfg(feature = "tokio03")]
+impl tokio03_crate::io::AsyncWrite for Mock {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Pending
+    }
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Poll::Pending
+    }
+    fn poll_shutdown(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "tokio03")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn tokio03_async_write() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = tokio03_crate::io::AsyncWrite::poll_write(x, cx, &[0]);
+        },
+    );
+}
+#[cfg(feature = "tokio03")]
+impl tokio03_crate::io::AsyncSeek for Mock {
+    fn start_seek(self: Pin<&mut Self>, _: std::io::SeekFrom) -> std::io::Result<()> {
+        Ok(())
+    }
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<std::io::Result<u64>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "tokio03")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn tokio03_async_seek() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = tokio03_crate::io::AsyncSeek::poll_complete(x, cx);
+        },
+    );
+}
+#[cfg(feature = "tokio03")]
+impl tokio03_crate::io::AsyncBufRead for Mock {
+    fn poll_fill_buf(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<std::io::Result<&[u8]>> {
+        Poll::Pending
+    }
+    fn consume(self: Pin<&mut Self>, _: usize) {}
+}
+#[cfg(feature = "tokio03")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn tokio03_async_buf_read() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = tokio03_crate::io::AsyncBufRead::poll_fill_buf(x, cx);
+        },
+    );
+}
+#[cfg(feature = "tokio1")]
+impl tokio1_crate::io::AsyncRead for Mock {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &mut tokio1_crate::io::ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "tokio1")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn tokio1_async_read() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = tokio1_crate::io::AsyncRead::poll_read(
+                x,
+                cx,
+                &mut tokio1_crate::io::ReadBuf::new(&mut [0; 1]),
+            );
+        },
+    );
+}
+#[cfg(feature = "tokio1")]
+impl tokio1_crate::io::AsyncWrite for Mock {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Pending
+    }
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Poll::Pending
+    }
+    fn poll_shutdown(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "tokio1")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn tokio1_async_write() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = tokio1_crate::io::AsyncWrite::poll_write(x, cx, &[0]);
+        },
+    );
+}
+#[cfg(feature = "tokio1")]
+impl tokio1_crate::io::AsyncSeek for Mock {
+    fn start_seek(self: Pin<&mut Self>, _: std::io::SeekFrom) -> std::io::Result<()> {
+        Ok(())
+    }
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<std::io::Result<u64>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "tokio1")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn tokio1_async_seek() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = tokio1_crate::io::AsyncSeek::poll_complete(x, cx);
+        },
+    );
+}
+#[cfg(feature = "tokio1")]
+impl tokio1_crate::io::AsyncBufRead for Mock {
+    fn poll_fill_buf(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<std::io::Result<&[u8]>> {
+        Poll::Pending
+    }
+    fn consume(self: Pin<&mut Self>, _: usize) {}
+}
+#[cfg(feature = "tokio1")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn tokio1_async_buf_read() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = tokio1_crate::io::AsyncBufRead::poll_fill_buf(x, cx);
+        },
+    );
+}
+#[cfg(feature = "http-body1")]
+impl http_body::Body for Mock {
+    type Data = &'static [u8];
+    type Error = ();
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, ()>>> {
+        Poll::Pending
+    }
+}
+#[cfg(feature = "http-body1")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn http_body1_body() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = http_body::Body::poll_frame(x, cx);
+        },
+    );
+}
+#[cfg(assert_unmoved_unstable)]
+impl core::ops::Coroutine for Mock {
+    type Yield = ();
+    type Return = ();
+    fn resume(self: Pin<&mut Self>, (): ()) -> core::ops::CoroutineState<(), ()> {
+        core::ops::CoroutineState::Yielded(())
+    }
+}
+#[cfg(assert_unmoved_unstable)]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn assert_unmoved_unstable_coroutine() {
+    assert_move_detected(
+        Mock,
+        |x, cx| {
+            let _ = {
+                let _ = cx;
+                core::ops::Coroutine::resume(x, ())
+            };
+        },
+    );
+}
+#[test]
+fn deref_mut_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = &mut **x;
+        },
+    );
+}
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn deref_mut() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = &mut **x;
+        },
+    );
+}
+#[test]
+fn as_mut_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = AsMut::<Mock>::as_mut(x);
+        },
+    );
+}
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn as_mut() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = AsMut::<Mock>::as_mut(x);
+        },
+    );
+}
+impl Iterator for Mock {
+    type Item = ();
+    fn next(&mut self) -> Option<()> {
+        None
+    }
+}
+#[test]
+fn iterator_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = Iterator::next(x);
+        },
+    );
+}
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn iterator() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = Iterator::next(x);
+        },
+    );
+}
+impl DoubleEndedIterator for Mock {
+    fn next_back(&mut self) -> Option<()> {
+        None
+    }
+}
+#[test]
+fn double_ended_iterator_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = DoubleEndedIterator::next_back(x);
+        },
+    );
+}
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn double_ended_iterator() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = DoubleEndedIterator::next_back(x);
+        },
+    );
+}
+impl core::fmt::Write for Mock {
+    fn write_str(&mut self, _: &str) -> core::fmt::Result {
+        Ok(())
+    }
+}
+#[test]
+fn write_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = core::fmt::Write::write_str(x, "");
+        },
+    );
+}
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn write() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = core::fmt::Write::write_str(x, "");
+        },
+    );
+}
+#[cfg(feature = "std")]
+impl std::io::Read for Mock {
+    fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+        Ok(0)
+    }
+}
+#[cfg(feature = "std")]
+#[test]
+fn std_read_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = std::io::Read::read(x, &mut [0; 1]);
+        },
+    );
+}
+#[cfg(feature = "std")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn std_read() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = std::io::Read::read(x, &mut [0; 1]);
+        },
+    );
+}
+#[cfg(feature = "std")]
+impl std::io::Write for Mock {
+    fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+        Ok(0)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+#[cfg(feature = "std")]
+#[test]
+fn std_write_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = std::io::Write::write(x, &[0]);
+        },
+    );
+}
+#[cfg(feature = "std")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn std_write() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = std::io::Write::write(x, &[0]);
+        },
+    );
+}
+#[cfg(feature = "std")]
+impl std::io::Seek for Mock {
+    fn seek(&mut self, _: std::io::SeekFrom) -> std::io::Result<u64> {
+        Ok(0)
+    }
+}
+#[cfg(feature = "std")]
+#[test]
+fn std_seek_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = std::io::Seek::seek(x, std::io::SeekFrom::Start(0));
+        },
+    );
+}
+#[cfg(feature = "std")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn std_seek() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = std::io::Seek::seek(x, std::io::SeekFrom::Start(0));
+        },
+    );
+}
+#[cfg(feature = "std")]
+impl std::io::BufRead for Mock {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(&[])
+    }
+    fn consume(&mut self, _: usize) {}
+}
+#[cfg(feature = "std")]
+#[test]
+fn std_buf_read_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = std::io::BufRead::fill_buf(x);
+        },
+    );
+}
+#[cfg(feature = "std")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn std_buf_read() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = std::io::BufRead::fill_buf(x);
+        },
+    );
+}
+#[cfg(feature = "bytes1")]
+impl bytes1_crate::Buf for Mock {
+    fn remaining(&self) -> usize {
+        0
+    }
+    fn chunk(&self) -> &[u8] {
+        &[]
+    }
+    fn advance(&mut self, _: usize) {}
+}
+#[cfg(feature = "bytes1")]
+#[test]
+fn bytes1_buf_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = bytes1_crate::Buf::copy_to_bytes(x, 0);
+        },
+    );
+}
+#[cfg(feature = "bytes1")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn bytes1_buf() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = bytes1_crate::Buf::copy_to_bytes(x, 0);
+        },
+    );
+}
+#[cfg(feature = "bytes1")]
+unsafe impl bytes1_crate::BufMut for Mock {
+    fn remaining_mut(&self) -> usize {
+        0
+    }
+    unsafe fn advance_mut(&mut self, _: usize) {}
+    fn chunk_mut(&mut self) -> &mut bytes1_crate::buf::UninitSlice {
+        bytes1_crate::buf::UninitSlice::new(&mut [])
+    }
+}
+#[cfg(feature = "bytes1")]
+#[test]
+fn bytes1_buf_mut_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = bytes1_crate::BufMut::chunk_mut(x);
+        },
+    );
+}
+#[cfg(feature = "bytes1")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn bytes1_buf_mut() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = bytes1_crate::BufMut::chunk_mut(x);
+        },
+    );
+}
+#[cfg(feature = "tokio-util07")]
+impl tokio_util07_crate::codec::Decoder for Mock {
+    type Item = ();
+    type Error = std::io::Error;
+    fn decode(&mut self, _: &mut bytes1_crate::BytesMut) -> std::io::Result<Option<()>> {
+        Ok(None)
+    }
+}
+#[cfg(feature = "tokio-util07")]
+#[test]
+fn tokio_util07_decoder_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = tokio_util07_crate::codec::Decoder::decode(
+                x,
+                &mut bytes1_crate::BytesMut::new(),
+            );
+        },
+    );
+}
+#[cfg(feature = "tokio-util07")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn tokio_util07_decoder() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = tokio_util07_crate::codec::Decoder::decode(
+                x,
+                &mut bytes1_crate::BytesMut::new(),
+            );
+        },
+    );
+}
+#[cfg(feature = "tokio-util07")]
+impl tokio_util07_crate::codec::Encoder<()> for Mock {
+    type Error = std::io::Error;
+    fn encode(&mut self, (): (), _: &mut bytes1_crate::BytesMut) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+#[cfg(feature = "tokio-util07")]
+#[test]
+fn tokio_util07_encoder_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = tokio_util07_crate::codec::Encoder::encode(
+                x,
+                (),
+                &mut bytes1_crate::BytesMut::new(),
+            );
+        },
+    );
+}
+#[cfg(feature = "tokio-util07")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn tokio_util07_encoder() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = tokio_util07_crate::codec::Encoder::encode(
+                x,
+                (),
+                &mut bytes1_crate::BytesMut::new(),
+            );
+        },
+    );
+}
+#[cfg(feature = "tower-service03")]
+impl tower_service::Service<()> for Mock {
+    type Response = ();
+    type Error = ();
+    type Future = core::future::Ready<Result<(), ()>>;
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, (): ()) -> Self::Future {
+        core::future::ready(Ok(()))
+    }
+}
+#[cfg(feature = "tower-service03")]
+#[test]
+fn tower_service03_service_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = tower_service::Service::call(x, ());
+        },
+    );
+}
+#[cfg(feature = "tower-service03")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn tower_service03_service() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = tower_service::Service::call(x, ());
+        },
+    );
+}
+#[cfg(feature = "embedded-hal-async1")]
+impl embedded_hal_async::spi::ErrorType for Mock {
+    type Error = core::convert::Infallible;
+}
+#[cfg(feature = "embedded-hal-async1")]
+impl embedded_hal_async::spi::SpiBus for Mock {
+    async fn read(&mut self, _: &mut [u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    async fn write(&mut self, _: &[u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    async fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    async fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+#[cfg(feature = "embedded-hal-async1")]
+#[test]
+fn embedded_hal_async1_spi_bus_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = embedded_hal_async::spi::SpiBus::write(x, &[0]);
+        },
+    );
+}
+#[cfg(feature = "embedded-hal-async1")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn embedded_hal_async1_spi_bus() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = embedded_hal_async::spi::SpiBus::write(x, &[0]);
+        },
+    );
+}
+#[cfg(feature = "embedded-hal-async1")]
+impl embedded_hal_async::i2c::ErrorType for Mock {
+    type Error = core::convert::Infallible;
+}
+#[cfg(feature = "embedded-hal-async1")]
+impl embedded_hal_async::i2c::I2c for Mock {
+    async fn transaction(
+        &mut self,
+        _: u8,
+        _: &mut [embedded_hal_async::i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+#[cfg(feature = "embedded-hal-async1")]
+#[test]
+fn embedded_hal_async1_i2c_not_pinned() {
+    assert_move_allowed_before_pin(
+        Mock,
+        |x| {
+            let _ = embedded_hal_async::i2c::I2c::write(x, 0, &[0]);
+        },
+    );
+}
+#[cfg(feature = "embedded-hal-async1")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn embedded_hal_async1_i2c() {
+    assert_move_detected_after_pin(
+        Mock,
+        |x| {
+            let _ = embedded_hal_async::i2c::I2c::write(x, 0, &[0]);
+        },
+    );
+}
@@ -95,25 +95,76 @@ let _ = pinned_next.as_mut().poll(&mut cx).is_pending();
 
 ## Optional features
 
-- **`futures03`** — Implements [futures v0.3][futures03] traits for assert-unmoved types.
+- **`std`** — Enabled by default. Implements `std` traits such as `std::io::Read` and `std::error::Error` for assert-unmoved types. Without this feature, this crate is `no_std`. Since there is then no way to know whether the thread is panicking, and panicking again while unwinding would abort the process, `AssertUnmoved` checks for moves on drop only when panics abort (`-C panic=abort`, detected on Rust 1.60+). Moves are still detected by `get_pin_mut` and the other methods.
+- **`alloc`** — Enabled by `std`. Implements conversions between assert-unmoved types and `Box`, `Rc`, and `Arc`.
+- **`futures03`** — Implements [futures v0.3][futures03] traits for assert-unmoved types. This also covers the I/O traits of [async-std] and [smol], which are re-exports of the `futures-io` ones, and types implementing them such as [`async_io::Async`].
+- **`futures-core03`** — Enabled by `futures03`. Implements the [futures-core v0.3][futures-core03] and [futures-sink v0.3][futures-sink03] traits (`Stream`, `Sink`, etc.) for assert-unmoved types. Unlike `futures03`, this does not require `std`.
 - **`tokio1`** — Implements [tokio v1][tokio1] traits for assert-unmoved types.
 - **`tokio03`** — Implements [tokio v0.3][tokio03] traits for assert-unmoved types.
 - **`tokio02`** — Implements [tokio v0.2][tokio02] traits for assert-unmoved types.
+- **`tokio-util07`** — Implements [tokio-util v0.7][tokio-util07] traits for assert-unmoved types.
+- **`bytes1`** — Implements [bytes v1][bytes1] traits for assert-unmoved types.
+- **`tower-service03`** — Implements [tower-service v0.3][tower-service03] traits (re-exported by [tower]) for assert-unmoved types.
+- **`http-body1`** — Implements [http-body v1][http-body1] traits (used by [hyper] and [axum]) for assert-unmoved types.
+- **`embedded-hal-async1`** — Implements [embedded-hal-async v1][embedded-hal-async1] traits for assert-unmoved types.
+- **`serde1`** — Implements [serde v1][serde1] traits for assert-unmoved types.
+- **`tracing01`** — Emits a [tracing v0.1][tracing01] error event before panicking when a move is detected.
+- **`log04`** — Logs a [log v0.4][log04] error record before panicking when a move is detected.
+- **`testing`** — Provides the `testing` module and the `assert_pin_stable!`, `assert_stream_stable!`, and `pin_and_poll!` macros, which help poll futures and streams in tests.
+- **`macros`** — Provides the [`#[assert_pinned_fields]`][assert_pinned_fields] attribute, which wraps the pinned fields of a [pin-project] type in `AssertUnmoved` in tests.
 
 Note: The MSRV when these features are enabled depends on the MSRV of these crates.
 
+## Optional cfg
+
+One of the ways to enable a cfg is to set [rustflags in the cargo config](https://doc.rust-lang.org/cargo/reference/config.html#targettriplerustflags):
+
+```toml
+# .cargo/config.toml
+[target.<target>]
+rustflags = ["--cfg", "assert_unmoved_unstable"]
+```
+
+Or set the environment variable:
+
+```sh
+RUSTFLAGS="--cfg assert_unmoved_unstable" cargo +nightly test
+```
+
+- **`--cfg assert_unmoved_unstable`** — Implements unstable traits such as [`Coroutine`] for assert-unmoved types. This requires a nightly compiler and is not covered by the semver guarantees. This is a cfg rather than a Cargo feature, so `--all-features` keeps working on stable.
+
+[`Coroutine`]: https://doc.rust-lang.org/nightly/core/ops/trait.Coroutine.html
 [`Pin::new_unchecked`]: https://doc.rust-lang.org/std/pin/struct.Pin.html#method.new_unchecked
 [`StreamExt::next`]: https://docs.rs/futures/latest/futures/stream/trait.StreamExt.html#method.next
+[`async_io::Async`]: https://docs.rs/async-io/latest/async_io/struct.Async.html
+[assert_pinned_fields]: https://docs.rs/assert-unmoved/latest/assert_unmoved/attr.assert_pinned_fields.html
+[async-std]: https://docs.rs/async-std
+[axum]: https://docs.rs/axum
+[bytes1]: https://docs.rs/bytes/1
+[embedded-hal-async1]: https://docs.rs/embedded-hal-async/1
 [futures-test]: https://docs.rs/futures-test
 [futures03]: https://docs.rs/futures/0.3
+[futures-core03]: https://docs.rs/futures-core/0.3
+[futures-sink03]: https://docs.rs/futures-sink/0.3
+[http-body1]: https://docs.rs/http-body/1
+[hyper]: https://docs.rs/hyper
+[log04]: https://docs.rs/log/0.4
 [pin]: https://doc.rust-lang.org/std/pin/index.html
+[pin-project]: https://docs.rs/pin-project
 [rust-lang/futures-rs#2148]: https://github.com/rust-lang/futures-rs/pull/2148
 [rust-lang/futures-rs#2208]: https://github.com/rust-lang/futures-rs/pull/2208
+[serde1]: https://docs.rs/serde/1
+[smol]: https://docs.rs/smol
 [tokio02]: https://docs.rs/tokio/0.2
 [tokio03]: https://docs.rs/tokio/0.3
 [tokio1]: https://docs.rs/tokio/1
+[tokio-util07]: https://docs.rs/tokio-util/0.7
+[tower-service03]: https://docs.rs/tower-service/0.3
+[tower]: https://docs.rs/tower
+[tracing01]: https://docs.rs/tracing/0.1
 */
 
+#![no_std]
 #![doc(test(
     no_crate_inject,
     attr(
@@ -135,12 +186,47 @@ Note: The MSRV when these features are enabled depends on the MSRV of these crat
     // clippy::std_instead_of_alloc,
     clippy::std_instead_of_core,
 )]
+#![cfg_attr(assert_unmoved_unstable, feature(coroutine_trait))]
 // docs.rs only (cfg is enabled by docs.rs, not build script)
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
 #[cfg(test)]
 #[path = "gen/tests/assert_impl.rs"]
 mod assert_impl;
+#[cfg(test)]
+#[path = "gen/tests/move_detection.rs"]
+mod move_detection;
+
+#[macro_use]
+mod forward;
 
 mod assert_unmoved;
 pub use crate::assert_unmoved::AssertUnmoved;
+
+mod error;
+pub use crate::error::{MoveError, MoveErrorKind};
+
+mod no_check;
+pub use crate::no_check::NoCheck;
+
+#[cfg(any(test, feature = "testing"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
+
+#[cfg(feature = "macros")]
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+pub use assert_unmoved_macros::assert_pinned_fields;
+
+#[cfg(feature = "std")]
+mod soft_mode;
+#[cfg(feature = "std")]
+pub use crate::soft_mode::set_soft_mode_handler;
+#[cfg(feature = "std")]
+mod strict_drop;
+#[cfg(feature = "std")]
+pub use crate::strict_drop::set_strict_drop;
@@ -0,0 +1,328 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, rc::Rc, sync::Arc};
+use core::{
+    future::Future,
+    ops,
+    panic::Location,
+    pin::Pin,
+    ptr,
+    task::{Context, Poll},
+};
+
+use pin_project_lite::pin_project;
+
+use crate::error::MoveError;
+
+pin_project! {
+    /// A type that has the same API as [`AssertUnmoved`](crate::AssertUnmoved),
+    /// but does not check anything.
+    ///
+    /// This is a transparent wrapper with no tracking overhead, for use in
+    /// benchmarks or production builds where the checks of `AssertUnmoved`
+    /// are not wanted.
+    ///
+    /// # Switching with a feature
+    ///
+    /// This crate does not provide a feature that replaces `AssertUnmoved` with
+    /// `NoCheck`: Cargo features are additive and unified across the
+    /// dependency graph, so such a feature enabled by one crate would silently
+    /// disable the checks in all others. Instead, define a feature and a type
+    /// alias in your own crate:
+    ///
+    /// ```toml
+    /// [features]
+    /// # Checks that pinned values are not moved.
+    /// assert-unmoved = []
+    /// ```
+    ///
+    /// ```
+    /// # #![allow(unexpected_cfgs)]
+    /// #[cfg(feature = "assert-unmoved")]
+    /// type MaybeAssertUnmoved<T> = assert_unmoved::AssertUnmoved<T>;
+    /// #[cfg(not(feature = "assert-unmoved"))]
+    /// type MaybeAssertUnmoved<T> = assert_unmoved::NoCheck<T>;
+    ///
+    /// let v = MaybeAssertUnmoved::new(1);
+    /// assert_eq!(*v, 1);
+    /// ```
+    ///
+    /// Use `cfg(test)` instead of `cfg(feature = "...")` to check only in
+    /// the unit tests of the crate that defines the alias.
+    ///
+    /// Like `AssertUnmoved`, this is always `!Unpin` and supports dynamically
+    /// sized `T`, so code that compiles with one of them also compiles with
+    /// the other.
+    #[project(!Unpin)]
+    #[derive(Debug)]
+    pub struct NoCheck<T: ?Sized> {
+        #[pin]
+        inner: T,
+    }
+}
+
+impl<T> NoCheck<T> {
+    /// Creates a new `NoCheck`.
+    #[must_use]
+    pub const fn new(inner: T) -> Self {
+        Self { inner }
+    }
+
+    /// Creates a new `NoCheck`. The tag is ignored.
+    #[must_use]
+    pub const fn with_tag(inner: T, _tag: &'static &'static str) -> Self {
+        Self::new(inner)
+    }
+
+    /// Creates a new `NoCheck` in a [`Box`].
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    pub fn new_box(inner: T) -> Box<Self> {
+        Box::new(Self::new(inner))
+    }
+
+    /// Creates a new pinned `NoCheck` in a [`Box`].
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    pub fn pin_box(inner: T) -> Pin<Box<Self>> {
+        Box::pin(Self::new(inner))
+    }
+
+    /// Creates a new pinned `NoCheck` in a [`Rc`].
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    pub fn pin_rc(inner: T) -> Pin<Rc<Self>> {
+        Rc::pin(Self::new(inner))
+    }
+
+    /// Creates a new pinned `NoCheck` in a [`Arc`].
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    pub fn pin_arc(inner: T) -> Pin<Arc<Self>> {
+        Arc::pin(Self::new(inner))
+    }
+}
+
+// The signatures must match those of `AssertUnmoved`.
+#[allow(clippy::unnecessary_wraps, clippy::unused_self)]
+impl<T: ?Sized> NoCheck<T> {
+    /// Gets a reference to the underlying type.
+    #[must_use]
+    pub const fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Calls the given closure with a reference to the underlying type.
+    ///
+    /// Like [`AssertUnmoved::inspect`](crate::AssertUnmoved::inspect), this is
+    /// an associated function.
+    pub fn inspect<R, F: FnOnce(&T) -> R>(this: &Self, f: F) -> R {
+        f(this.get_ref())
+    }
+
+    /// Calls the given closure with a pinned reference to the underlying type.
+    pub fn inspect_pin_ref<R, F: FnOnce(Pin<&T>) -> R>(this: Pin<&Self>, f: F) -> R {
+        f(Self::as_inner_pin(this))
+    }
+
+    /// Gets a mutable reference to the underlying type.
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Gets a pinned mutable reference to the underlying type.
+    #[must_use]
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        self.project().inner
+    }
+
+    /// Gets a pinned mutable reference to the underlying type.
+    ///
+    /// This never returns an error.
+    pub fn try_get_pin_mut(self: Pin<&mut Self>) -> Result<Pin<&mut T>, MoveError> {
+        Ok(self.get_pin_mut())
+    }
+
+    /// Does nothing.
+    pub fn snapshot_addr(self: Pin<&mut Self>) {}
+
+    /// Polls the underlying future without requiring the caller to pin
+    /// `NoCheck`.
+    ///
+    /// # Safety
+    ///
+    /// This pins `NoCheck` in place, so the caller must guarantee that it is
+    /// not moved between calls or before drop, as with [`Pin::new_unchecked`].
+    pub unsafe fn poll_once_unchecked(&mut self, cx: &mut Context<'_>) -> Poll<T::Output>
+    where
+        T: Future,
+    {
+        // SAFETY: the caller must guarantee that this `NoCheck` is not moved
+        // after this call.
+        unsafe { Pin::new_unchecked(self) }.get_pin_mut().poll(cx)
+    }
+
+    /// Gets a pinned reference to the underlying type.
+    #[must_use]
+    pub fn as_inner_pin(this: Pin<&Self>) -> Pin<&T> {
+        this.project_ref().inner
+    }
+
+    /// Returns `false`, since `NoCheck` does not track anything.
+    #[must_use]
+    pub const fn is_pinned_accessed(&self) -> bool {
+        false
+    }
+
+    /// Returns `None`, since `NoCheck` does not track anything.
+    #[must_use]
+    pub const fn pinned_at_location(&self) -> Option<&'static Location<'static>> {
+        None
+    }
+
+    /// Returns the current address of this `NoCheck`.
+    #[must_use]
+    pub fn get_addr(&self) -> usize {
+        (self as *const Self).cast::<()>() as usize
+    }
+
+    /// Returns `None`, since `NoCheck` does not track anything.
+    #[must_use]
+    pub const fn get_recorded_addr(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns `0`, since `NoCheck` does not track anything.
+    #[cfg(feature = "testing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+    #[must_use]
+    pub const fn get_pin_mut_call_count(&self) -> usize {
+        0
+    }
+
+    /// Does nothing.
+    ///
+    /// # Safety
+    ///
+    /// This has the same safety contract as
+    /// [`AssertUnmoved::reset_pin_state`](crate::AssertUnmoved::reset_pin_state).
+    pub unsafe fn reset_pin_state(self: Pin<&mut Self>) {}
+
+    /// Returns `true` if `self` and `other` are the same `NoCheck`, i.e., they
+    /// point to the same memory location.
+    #[must_use]
+    pub fn is_same_location_as(&self, other: &Self) -> bool {
+        ptr::eq(self, other)
+    }
+
+    #[cfg(feature = "futures-core03")]
+    fn get_pin_mut_in(self: Pin<&mut Self>, _method: &'static str) -> Pin<&mut T> {
+        self.get_pin_mut()
+    }
+}
+
+// The signatures must match those of `AssertUnmoved`.
+#[allow(clippy::unnecessary_wraps)]
+impl<T> NoCheck<T> {
+    /// Unwraps the value.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Unwraps the value.
+    ///
+    /// This never returns an error.
+    pub fn into_inner_if_unpinned(self) -> Result<T, Self> {
+        Ok(self.inner)
+    }
+
+    /// Replaces the underlying value with the given value, returning the old
+    /// value.
+    pub fn replace_inner(&mut self, inner: T) -> T {
+        core::mem::replace(&mut self.inner, inner)
+    }
+
+    /// Maps the underlying value to a new value of possibly another type.
+    pub fn map_inner<U, F: FnOnce(T) -> U>(self, f: F) -> NoCheck<U> {
+        NoCheck::new(f(self.inner))
+    }
+
+    /// Moves this `NoCheck` into a pinned [`Box`].
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[must_use]
+    pub fn into_pin_box(self) -> Pin<Box<Self>> {
+        Box::pin(self)
+    }
+}
+
+impl<T> NoCheck<NoCheck<T>> {
+    /// Removes one level of nesting.
+    #[must_use]
+    pub fn flatten(self) -> NoCheck<T> {
+        self.inner
+    }
+}
+
+impl<T: ?Sized + Unpin> ops::DerefMut for NoCheck<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.get_mut()
+    }
+}
+
+impl<T: ?Sized + Unpin> AsMut<T> for NoCheck<T> {
+    fn as_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+impl<T: Clone> Clone for NoCheck<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+impl<T> From<T> for NoCheck<T> {
+    fn from(inner: T) -> Self {
+        Self::new(inner)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<T> From<NoCheck<T>> for Arc<T> {
+    fn from(this: NoCheck<T>) -> Self {
+        Self::new(this.into_inner())
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<T> From<NoCheck<T>> for Rc<T> {
+    fn from(this: NoCheck<T>) -> Self {
+        Self::new(this.into_inner())
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<T: Unpin> From<Pin<Box<T>>> for Pin<Box<NoCheck<T>>> {
+    fn from(pinned: Pin<Box<T>>) -> Self {
+        Box::pin(NoCheck::new(*Pin::into_inner(pinned)))
+    }
+}
+
+impl<T: Default> Default for NoCheck<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+forward_impls!(NoCheck);
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use core::{
+    mem, ptr,
+    sync::atomic::{AtomicPtr, AtomicU8, Ordering},
+};
+use std::{env, eprintln};
+
+use crate::error::MoveError;
+
+const UNINIT: u8 = 0;
+const DISABLED: u8 = 1;
+const ENABLED: u8 = 2;
+
+static STATE: AtomicU8 = AtomicU8::new(UNINIT);
+
+// A `fn(MoveError)` set by `set_soft_mode_handler`, or null for the default
+// handler. (`OnceLock` and const `RwLock::new` are not available on MSRV.)
+static HANDLER: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Sets the handler called when a move is detected on drop in soft mode.
+///
+/// Soft mode is enabled by setting the `ASSERT_UNMOVED_SOFT` environment
+/// variable to `1`. The variable is read lazily, the first time a move is
+/// detected on drop, not at process start; changing it after that has no
+/// effect. In soft mode, a move detected when an `AssertUnmoved` is dropped
+/// calls the handler instead of panicking. The default handler prints the
+/// error to stderr.
+///
+/// Moves detected by other methods, such as
+/// [`get_pin_mut`](crate::AssertUnmoved::get_pin_mut), always panic, because
+/// continuing would hand out a reference to the moved value, which is
+/// undefined behavior for self-referential types. Use
+/// [`try_get_pin_mut`](crate::AssertUnmoved::try_get_pin_mut) to handle them
+/// without panicking.
+///
+/// The handler is global, so it is also called for moves detected on other
+/// threads, such as the worker threads of an executor.
+///
+/// In [strict drop mode](crate::set_strict_drop), the handler is also called
+/// for moves detected on drop while the thread is panicking, regardless of
+/// whether soft mode is enabled.
+///
+/// Otherwise, this has no effect when soft mode is not enabled.
+///
+/// # Examples
+///
+/// ```
+/// use assert_unmoved::MoveError;
+///
+/// fn handler(e: MoveError) {
+///     println!("move detected: {}", e);
+/// }
+///
+/// assert_unmoved::set_soft_mode_handler(handler);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn set_soft_mode_handler(handler: fn(MoveError)) {
+    HANDLER.store(handler as *mut (), Ordering::Release);
+}
+
+fn default_handler(e: MoveError) {
+    eprintln!("{}", e);
+}
+
+/// Returns `true` if the `ASSERT_UNMOVED_SOFT` environment variable was set to
+/// `1` when this was first called.
+pub(crate) fn enabled() -> bool {
+    match STATE.load(Ordering::Relaxed) {
+        DISABLED => false,
+        ENABLED => true,
+        _ => {
+            let enabled = matches!(env::var_os("ASSERT_UNMOVED_SOFT"), Some(v) if v == "1");
+            STATE.store(if enabled { ENABLED } else { DISABLED }, Ordering::Relaxed);
+            enabled
+        }
+    }
+}
+
+/// Calls the soft mode handler.
+pub(crate) fn handle(e: MoveError) {
+    let handler = HANDLER.load(Ordering::Acquire);
+    let handler: fn(MoveError) = if handler.is_null() {
+        default_handler
+    } else {
+        // SAFETY: non-null values are only stored by `set_soft_mode_handler`,
+        // which casts them from a `fn(MoveError)`.
+        unsafe { mem::transmute::<*mut (), fn(MoveError)>(handler) }
+    };
+    handler(e);
+}
@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables strict drop mode.
+///
+/// By default, `AssertUnmoved` skips the check on drop if the thread is
+/// already panicking, because panicking again would abort the process. This
+/// can hide a move that happened before the test failed for another reason.
+/// In strict drop mode, such a move is reported instead of being silently
+/// ignored: it is emitted to the enabled logging integrations and passed to
+/// the handler set by [`set_soft_mode_handler`](crate::set_soft_mode_handler),
+/// which prints it to stderr by default.
+///
+/// This is configured per process and is disabled by default.
+///
+/// # Examples
+///
+/// ```
+/// assert_unmoved::set_strict_drop(true);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn set_strict_drop(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns `true` if strict drop mode is enabled.
+pub(crate) fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
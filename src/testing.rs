@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Utilities for writing tests.
+//!
+//! These do not require `futures` or `tokio`, so tests can poll futures
+//! without adding them as dependencies just for this.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    ptr,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use crate::AssertUnmoved;
+
+/// Wraps the future in [`AssertUnmoved`], pins it, and polls it up to the
+/// given number of times with a no-op waker.
+///
+/// Polling stops early once the future completes. This evaluates to the result
+/// of the last poll.
+///
+/// # Panics
+///
+/// Panics if a move is detected while polling, e.g., by an `AssertUnmoved`
+/// held by the future.
+///
+/// # Examples
+///
+/// ```
+/// use std::{future, task::Poll};
+///
+/// use assert_unmoved::assert_pin_stable;
+///
+/// assert_eq!(assert_pin_stable!(future::pending::<()>(), 3), Poll::Pending);
+/// assert_eq!(assert_pin_stable!(async { 1 }, 3), Poll::Ready(1));
+/// ```
+#[macro_export]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+macro_rules! assert_pin_stable {
+    ($future:expr, $n:expr $(,)?) => {
+        $crate::testing::assert_stable($future, $n)
+    };
+}
+
+/// Wraps the future in [`AssertUnmoved`], pins it, and polls it once with the
+/// given context.
+///
+/// This evaluates to the result of the poll. The future is dropped afterward.
+///
+/// # Examples
+///
+/// ```
+/// use std::{future, task::Context};
+///
+/// use assert_unmoved::pin_and_poll;
+/// use futures::task::noop_waker;
+///
+/// let waker = noop_waker();
+/// let mut cx = Context::from_waker(&waker);
+/// assert!(pin_and_poll!(future::pending::<()>(), &mut cx).is_pending());
+/// assert_eq!(pin_and_poll!(future::ready(1), &mut cx), 1.into());
+/// ```
+#[macro_export]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+macro_rules! pin_and_poll {
+    ($future:expr, $cx:expr $(,)?) => {
+        $crate::testing::__pin_and_poll($future, $cx)
+    };
+}
+
+/// Wraps the stream in [`AssertUnmoved`], pins it, and polls it up to the
+/// given number of times with a no-op waker.
+///
+/// Polling stops early once the stream is exhausted. This evaluates to a
+/// `Vec` of the items yielded.
+///
+/// # Panics
+///
+/// Panics if a move is detected while polling, e.g., by an `AssertUnmoved`
+/// held by the stream.
+///
+/// # Examples
+///
+/// ```
+/// use assert_unmoved::assert_stream_stable;
+/// use futures::stream;
+///
+/// assert_eq!(assert_stream_stable!(stream::iter(vec![1, 2]), 5), [1, 2]);
+/// assert_eq!(assert_stream_stable!(stream::pending::<()>(), 5), []);
+/// ```
+#[cfg(feature = "futures03")]
+#[macro_export]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "testing", feature = "futures03"))))]
+macro_rules! assert_stream_stable {
+    ($stream:expr, $n:expr $(,)?) => {
+        $crate::testing::__assert_stream_stable($stream, $n)
+    };
+}
+
+/// Polls the future once with a no-op waker.
+///
+/// # Examples
+///
+/// ```
+/// use std::future;
+///
+/// use assert_unmoved::{testing::poll_once, AssertUnmoved};
+///
+/// let mut future = Box::pin(AssertUnmoved::new(future::ready(1)));
+/// assert_eq!(poll_once(future.as_mut()), 1.into());
+/// ```
+#[track_caller]
+pub fn poll_once<F: Future + ?Sized>(future: Pin<&mut F>) -> Poll<F::Output> {
+    future.poll(&mut noop_cx())
+}
+
+/// Polls the stream once with a no-op waker.
+///
+/// # Examples
+///
+/// ```
+/// use assert_unmoved::{testing::poll_stream_once, AssertUnmoved};
+/// use futures::stream;
+///
+/// let mut stream = Box::pin(AssertUnmoved::new(stream::iter(vec![1])));
+/// assert_eq!(poll_stream_once(stream.as_mut()), Some(1).into());
+/// assert_eq!(poll_stream_once(stream.as_mut()), None.into());
+/// ```
+#[cfg(feature = "futures03")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures03")))]
+#[track_caller]
+pub fn poll_stream_once<S: futures_core::Stream + ?Sized>(
+    stream: Pin<&mut S>,
+) -> Poll<Option<S::Item>> {
+    stream.poll_next(&mut noop_cx())
+}
+
+/// Wraps the future in [`AssertUnmoved`], pins it, and polls it up to the
+/// given number of times with a no-op waker.
+///
+/// This is the function form of [`assert_pin_stable!`](crate::assert_pin_stable).
+///
+/// # Panics
+///
+/// Panics if a move is detected while polling, e.g., by an `AssertUnmoved`
+/// held by the future.
+///
+/// # Examples
+///
+/// ```
+/// use std::future;
+///
+/// use assert_unmoved::testing::assert_stable;
+///
+/// assert!(assert_stable(future::pending::<()>(), 3).is_pending());
+/// ```
+#[track_caller]
+pub fn assert_stable<F: Future>(future: F, n: usize) -> Poll<F::Output> {
+    let mut future = AssertUnmoved::new(future);
+    // SAFETY: `future` is shadowed and never moved.
+    let mut future = unsafe { Pin::new_unchecked(&mut future) };
+    for _ in 0..n {
+        if let Poll::Ready(output) = poll_once(future.as_mut()) {
+            return Poll::Ready(output);
+        }
+    }
+    Poll::Pending
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn __pin_and_poll<F: Future>(future: F, cx: &mut Context<'_>) -> Poll<F::Output> {
+    let mut future = AssertUnmoved::new(future);
+    // SAFETY: `future` is shadowed and never moved.
+    let future = unsafe { Pin::new_unchecked(&mut future) };
+    future.poll(cx)
+}
+
+#[cfg(feature = "futures03")]
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_stream_stable<S: futures_core::Stream>(
+    stream: S,
+    n: usize,
+) -> std::vec::Vec<S::Item> {
+    let mut stream = AssertUnmoved::new(stream);
+    // SAFETY: `stream` is shadowed and never moved.
+    let mut stream = unsafe { Pin::new_unchecked(&mut stream) };
+    let mut items = std::vec::Vec::new();
+    for _ in 0..n {
+        match poll_stream_once(stream.as_mut()) {
+            Poll::Ready(Some(item)) => items.push(item),
+            Poll::Ready(None) => break,
+            Poll::Pending => {}
+        }
+    }
+    items
+}
+
+/// Creates a new [`Waker`] that does nothing when woken.
+///
+/// # Examples
+///
+/// ```
+/// use std::task::Context;
+///
+/// use assert_unmoved::testing::noop_waker;
+///
+/// let waker = noop_waker();
+/// let cx = Context::from_waker(&waker);
+/// cx.waker().wake_by_ref();
+/// ```
+#[must_use]
+pub fn noop_waker() -> Waker {
+    // SAFETY: All functions of the vtable do nothing with the data pointer,
+    // so the contract of `RawWaker` is trivially upheld.
+    unsafe { Waker::from_raw(noop_raw_waker()) }
+}
+
+/// Creates a new [`Context`] whose waker does nothing when woken.
+///
+/// # Examples
+///
+/// ```
+/// use std::future::Future;
+///
+/// use assert_unmoved::{testing::noop_cx, AssertUnmoved};
+///
+/// let mut cx = noop_cx();
+/// let mut future = Box::pin(AssertUnmoved::new(async { 1 }));
+/// assert_eq!(future.as_mut().poll(&mut cx), 1.into());
+/// ```
+#[must_use]
+pub fn noop_cx() -> Context<'static> {
+    Context::from_waker(noop_waker_ref())
+}
+
+fn noop_waker_ref() -> &'static Waker {
+    struct SyncRawWaker(RawWaker);
+    // SAFETY: The data pointer is null and all functions of the vtable do
+    // nothing, so it is fine to share the waker between threads.
+    unsafe impl Sync for SyncRawWaker {}
+
+    static NOOP_WAKER: SyncRawWaker = SyncRawWaker(noop_raw_waker());
+
+    // SAFETY: `Waker` is `#[repr(transparent)]` over `RawWaker`, and the
+    // contract of `RawWaker` is upheld as in `noop_waker`.
+    unsafe { &*(&NOOP_WAKER.0 as *const RawWaker).cast::<Waker>() }
+}
+
+const fn noop_raw_waker() -> RawWaker {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+    fn clone(_: *const ()) -> RawWaker {
+        noop_raw_waker()
+    }
+    fn noop(_: *const ()) {}
+
+    RawWaker::new(ptr::null(), &VTABLE)
+}
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Soft mode is configured per process, so this is a separate test binary.
+
+#![cfg(feature = "std")]
+#![allow(clippy::undocumented_unsafe_blocks)]
+
+use std::{
+    env,
+    future::{pending, Future as _},
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::Context,
+    thread,
+};
+
+use assert_unmoved::{AssertUnmoved, MoveError, MoveErrorKind};
+use futures::task::noop_waker;
+
+static REPORTED: AtomicUsize = AtomicUsize::new(0);
+
+fn handler(e: MoveError) {
+    assert_ne!(e.original_addr, e.current_addr);
+    assert_eq!(e.kind(), MoveErrorKind::BeforeDrop);
+    assert_eq!(e.method(), None);
+    REPORTED.fetch_add(1, Ordering::Relaxed);
+}
+
+#[test]
+fn soft_mode() {
+    // This test binary has only this test, so no other thread accesses the
+    // environment. (`set_var` is unsafe since Rust 2024.)
+    #[allow(unused_unsafe)]
+    unsafe {
+        env::set_var("ASSERT_UNMOVED_SOFT", "1");
+    }
+    assert_unmoved::set_soft_mode_handler(handler);
+
+    // A move detected on drop calls the handler instead of panicking, even if
+    // it is detected on another thread than the one that set the handler.
+    thread::spawn(|| {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut future = AssertUnmoved::new(pending::<()>());
+        assert!(unsafe { Pin::new_unchecked(&mut future) }.poll(&mut cx).is_pending());
+        drop(Box::new(future));
+    })
+    .join()
+    .unwrap();
+    assert_eq!(REPORTED.load(Ordering::Relaxed), 1);
+
+    // A move detected by `get_pin_mut` still panics, since it would otherwise
+    // hand out a pinned reference to the moved value.
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = AssertUnmoved::new(pending::<()>());
+    assert!(unsafe { Pin::new_unchecked(&mut future) }.poll(&mut cx).is_pending());
+    let mut future = Box::new(future);
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _ = unsafe { Pin::new_unchecked(&mut *future) }.poll(&mut cx);
+    }));
+    assert!(res.is_err());
+    drop(future);
+    // The move has already been reported, so it is not reported again on drop.
+    assert_eq!(REPORTED.load(Ordering::Relaxed), 1);
+}
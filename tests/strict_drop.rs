@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Strict drop mode is configured per process, so this is a separate test binary.
+
+#![cfg(feature = "std")]
+#![allow(clippy::undocumented_unsafe_blocks)]
+
+use std::{
+    future::{pending, Future as _},
+    panic,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::Context,
+};
+
+use assert_unmoved::{AssertUnmoved, MoveError, MoveErrorKind};
+use futures::task::noop_waker;
+
+static REPORTED: AtomicUsize = AtomicUsize::new(0);
+
+fn handler(e: MoveError) {
+    assert_eq!(e.kind(), MoveErrorKind::BeforeDrop);
+    assert_eq!(e.method(), None);
+    REPORTED.fetch_add(1, Ordering::Relaxed);
+}
+
+#[test]
+fn strict_drop() {
+    assert_unmoved::set_strict_drop(true);
+    assert_unmoved::set_soft_mode_handler(handler);
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = AssertUnmoved::new(pending::<()>());
+    assert!(unsafe { Pin::new_unchecked(&mut future) }.poll(&mut cx).is_pending());
+    let future = Box::new(future);
+    // The move is passed to the handler instead of panicking again, so the
+    // process is not aborted.
+    let res = panic::catch_unwind(panic::AssertUnwindSafe(move || {
+        let _future = future;
+        panic!("first panic");
+    }));
+    assert_eq!(*res.unwrap_err().downcast::<&str>().unwrap(), "first panic");
+    assert_eq!(REPORTED.load(Ordering::Relaxed), 1);
+}
@@ -1,82 +1,2467 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+#![cfg_attr(assert_unmoved_unstable, feature(coroutine_trait))]
 #![allow(clippy::let_underscore_future, clippy::undocumented_unsafe_blocks)]
 
 use std::{
-    future::{pending, Future, Pending},
+    future::{self, pending, Future, Pending},
     mem,
+    panic::{self, AssertUnwindSafe},
     pin::Pin,
-    task::Context,
+    task::{Context, Poll},
+    thread,
 };
 
 use assert_unmoved::*;
 use futures::task::noop_waker;
 
+#[cfg(any(
+    feature = "futures03",
+    feature = "tokio02",
+    feature = "tokio1",
+    feature = "embedded-hal-async1"
+))]
+fn block_on<F: Future>(future: F) -> F::Output {
+    use std::task::Poll;
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
 // Test the size of public types. This is not intended to keep a specific size and
 // is intended to be used only as a help in optimization.
 #[test]
 #[cfg_attr(any(not(target_pointer_width = "64"), miri, careful), ignore)] // We set -Z randomize-layout for Miri/cargo-careful.
 fn size() {
-    assert_eq!(mem::size_of::<AssertUnmoved<Pending<()>>>(), 16);
+    assert_eq!(
+        mem::size_of::<AssertUnmoved<Pending<()>>>(),
+        if cfg!(feature = "testing") { 32 } else { 24 }
+    );
+}
+
+#[test]
+fn do_not_panic_when_not_polled() {
+    // This shouldn't panic.
+    let future = AssertUnmoved::new(pending::<()>());
+    drop(future);
+}
+
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn do_not_double_panic() {
+    // This test should only panic, not abort the process.
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    // First we allocate the future on the stack and poll it.
+    let mut future = AssertUnmoved::new(pending::<()>());
+    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+    assert!(pinned_future.poll(&mut cx).is_pending());
+
+    // Next we move it back to the heap and poll it again. This second call
+    // should panic (as the future is moved), but we shouldn't panic again
+    // whilst dropping `AssertUnmoved`.
+    let mut future = Box::new(future);
+    let pinned_boxed_future = unsafe { Pin::new_unchecked(&mut *future) };
+    assert!(pinned_boxed_future.poll(&mut cx).is_pending());
+}
+
+#[test]
+#[should_panic(expected = "AssertUnmoved moved before drop: was at 0x")]
+fn moved_before_drop() {
+    struct Test<T>(Option<T>);
+
+    impl<T> Drop for Test<T> {
+        fn drop(&mut self) {
+            // This moves `T`.
+            self.0.take();
+        }
+    }
+
+    let mut x = Test(Some(AssertUnmoved::new(pending::<()>())));
+    let x = unsafe { Pin::new_unchecked(&mut x) };
+    // This `map_unchecked_mut` is unsound because `Test`'s destructor moves `T`.
+    let _ = unsafe { x.map_unchecked_mut(|x| &mut x.0) }.as_pin_mut().unwrap().get_pin_mut();
+}
+
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn misuse_get_mut() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = AssertUnmoved::new(pending::<()>());
+    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+    assert!(pinned_future.poll(&mut cx).is_pending());
+
+    let mut future = Box::new(future);
+    let _ = future.get_mut();
+}
+
+#[test]
+fn do_not_panic_on_drop_after_caught_violation() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = AssertUnmoved::new(pending::<()>());
+    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+    assert!(pinned_future.poll(&mut cx).is_pending());
+    let mut future = Box::new(future);
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        let pinned_future = unsafe { Pin::new_unchecked(&mut *future) };
+        let _ = pinned_future.poll(&mut cx);
+    }));
+    assert!(res.is_err());
+    // This shouldn't panic, the move has already been reported.
+    drop(future);
+
+    let mut future = AssertUnmoved::new(pending::<()>());
+    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+    assert!(pinned_future.poll(&mut cx).is_pending());
+    let mut future = Box::new(future);
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _ = future.get_mut();
+    }));
+    assert!(res.is_err());
+    // This shouldn't panic, the move has already been reported.
+    drop(future);
+}
+
+#[test]
+fn panic_message() {
+    #[track_caller]
+    fn panic_message(f: impl FnOnce()) -> String {
+        let payload = panic::catch_unwind(AssertUnwindSafe(f)).unwrap_err();
+        match payload.downcast::<String>() {
+            Ok(msg) => *msg,
+            Err(payload) => (*payload.downcast::<&str>().unwrap()).to_owned(),
+        }
+    }
+    #[track_caller]
+    fn assert_message(msg: &str, first_line: &str) {
+        assert!(msg.contains(&format!("AssertUnmoved {}", first_line)), "{}", msg);
+        assert!(
+            msg.contains(concat!("\tfirst pinned mutably accessed at ", file!(), ":")),
+            "{}",
+            msg
+        );
+    }
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let msg = panic_message(|| {
+        let mut future = AssertUnmoved::new(pending::<()>());
+        let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+        assert!(pinned_future.poll(&mut cx).is_pending());
+        let mut future = Box::new(future);
+        let pinned_future = unsafe { Pin::new_unchecked(&mut *future) };
+        let _ = pinned_future.poll(&mut cx);
+    });
+    assert_message(&msg, "moved between get_pin_mut calls\n");
+
+    let msg = panic_message(|| {
+        let mut future = AssertUnmoved::new(pending::<()>());
+        let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+        assert!(pinned_future.poll(&mut cx).is_pending());
+        let mut future = Box::new(future);
+        let _ = future.get_mut();
+    });
+    assert_message(&msg, "moved after get_pin_mut call (detected in get_mut)\n");
+
+    let msg = panic_message(|| {
+        let mut future = AssertUnmoved::new(pending::<()>());
+        let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+        assert!(pinned_future.poll(&mut cx).is_pending());
+        let _future = Box::new(future);
+    });
+    assert_message(&msg, "moved before drop: was at 0x");
+    assert!(msg.contains(", now at 0x"), "{}", msg);
+    // Addresses are shown in both hexadecimal and decimal.
+    let addrs = msg.split("was at ").nth(1).unwrap().lines().next().unwrap();
+    for addr in addrs.split(", now at ") {
+        let (hex, dec) = addr.trim_end_matches(')').split_once(" (").unwrap();
+        assert_eq!(usize::from_str_radix(&hex[2..], 16).unwrap(), dec.parse::<usize>().unwrap());
+    }
+}
+
+#[test]
+fn try_get_pin_mut() {
+    let mut future = AssertUnmoved::new(pending::<()>());
+    let line = line!() + 1;
+    assert!(unsafe { Pin::new_unchecked(&mut future) }.try_get_pin_mut().is_ok());
+    assert!(unsafe { Pin::new_unchecked(&mut future) }.try_get_pin_mut().is_ok());
+    let original_addr = &future as *const _ as usize;
+
+    let mut future = Box::new(future);
+    let current_addr = &*future as *const _ as usize;
+    let err = unsafe { Pin::new_unchecked(&mut *future) }.try_get_pin_mut().unwrap_err();
+    let detected_line = line!() - 1;
+    assert_eq!(err.original_addr, original_addr);
+    assert_eq!(err.current_addr, current_addr);
+    assert_eq!(err.first_pinned_at.file(), file!());
+    assert_eq!(err.first_pinned_at.line(), line);
+    assert_eq!(err.detected_at.unwrap().line(), detected_line);
+    assert_eq!(err.kind(), MoveErrorKind::BetweenGetPinMutCalls);
+    assert_eq!(err.method(), None);
+    let msg = err.to_string();
+    assert!(msg.starts_with("AssertUnmoved moved between get_pin_mut calls\n"), "{}", msg);
+    assert!(
+        msg.contains(&format!("\tfirst pinned mutably accessed at {}:{}:", file!(), line)),
+        "{}",
+        msg
+    );
+    assert!(msg.contains(&format!("\tdetected at {}:{}:", file!(), detected_line)), "{}", msg);
+    #[cfg(feature = "std")]
+    let _: &dyn std::error::Error = &err;
+    // Subsequent calls also return errors.
+    assert!(unsafe { Pin::new_unchecked(&mut *future) }.try_get_pin_mut().is_err());
+    // This shouldn't panic, the move has already been reported.
+    drop(future);
+}
+
+#[test]
+fn poll_once_unchecked() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = AssertUnmoved::new(pending::<()>());
+    assert!(unsafe { future.poll_once_unchecked(&mut cx) }.is_pending());
+    assert!(unsafe { future.poll_once_unchecked(&mut cx) }.is_pending());
+
+    let mut future = AssertUnmoved::new(future::ready(1));
+    assert_eq!(unsafe { future.poll_once_unchecked(&mut cx) }, Poll::Ready(1));
+
+    // `!Unpin` futures can be polled too.
+    let mut future = AssertUnmoved::new(async { 1 });
+    assert_eq!(unsafe { future.poll_once_unchecked(&mut cx) }, Poll::Ready(1));
+}
+
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn poll_once_unchecked_moved() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = AssertUnmoved::new(pending::<()>());
+    assert!(unsafe { future.poll_once_unchecked(&mut cx) }.is_pending());
+    // This breaks the contract of `poll_once_unchecked`, but `Pending` is `Unpin`, so
+    // moving it is fine except for the check by `AssertUnmoved`.
+    let mut future = Box::new(future);
+    let _ = unsafe { future.poll_once_unchecked(&mut cx) };
+}
+
+#[test]
+fn const_new() {
+    const X: AssertUnmoved<i32> = AssertUnmoved::new(42);
+    const Y: AssertUnmoved<()> = AssertUnmoved::with_tag((), &"y");
+    static Z: AssertUnmoved<&str> = AssertUnmoved::new("z");
+
+    assert_eq!(*X, 42);
+    assert!(!X.is_pinned_accessed());
+    assert_eq!(*Y, ());
+    assert_eq!(*Z, "z");
+    let mut x = Box::pin(X);
+    *x.as_mut().get_pin_mut() += 1;
+    assert_eq!(**x, 43);
+    assert_eq!(*X, 42);
+}
+
+#[test]
+fn with_tag() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let msg = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut future = AssertUnmoved::with_tag(pending::<()>(), &"my_future");
+        let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+        assert!(pinned_future.poll(&mut cx).is_pending());
+        let mut future = Box::new(future);
+        let _ = future.get_mut();
+    }))
+    .unwrap_err();
+    let msg = msg.downcast_ref::<String>().unwrap();
+    assert!(
+        msg.starts_with(
+            "AssertUnmoved[my_future] moved after get_pin_mut call (detected in get_mut)\n"
+        ),
+        "{}",
+        msg
+    );
+
+    let mut future = AssertUnmoved::with_tag(pending::<()>(), &"my_future");
+    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+    assert!(pinned_future.poll(&mut cx).is_pending());
+    let mut future = Box::new(future);
+    let err = unsafe { Pin::new_unchecked(&mut *future) }.try_get_pin_mut().unwrap_err();
+    assert_eq!(err.tag, Some("my_future"));
+    assert!(err
+        .to_string()
+        .starts_with("AssertUnmoved[my_future] moved between get_pin_mut calls\n"));
+    // The tag is kept by clone.
+    let mut future = (*future).clone();
+    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+    assert!(pinned_future.poll(&mut cx).is_pending());
+    let mut future = Box::new(future);
+    let err = unsafe { Pin::new_unchecked(&mut *future) }.try_get_pin_mut().unwrap_err();
+    assert_eq!(err.tag, Some("my_future"));
+
+    // Untagged instances don't have a tag.
+    let mut future = AssertUnmoved::new(pending::<()>());
+    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+    assert!(pinned_future.poll(&mut cx).is_pending());
+    let mut future = Box::new(future);
+    let err = unsafe { Pin::new_unchecked(&mut *future) }.try_get_pin_mut().unwrap_err();
+    assert_eq!(err.tag, None);
+}
+
+#[test]
+fn as_inner_pin() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    // Untracked: as_inner_pin does not start tracking.
+    let future = AssertUnmoved::new(pending::<()>());
+    let pinned_future = unsafe { Pin::new_unchecked(&future) };
+    let _: Pin<&Pending<()>> = AssertUnmoved::as_inner_pin(pinned_future);
+    let mut future = Box::pin(future);
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+
+    // Tracked
+    let _: Pin<&Pending<()>> = AssertUnmoved::as_inner_pin(future.as_ref());
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+}
+
+#[test]
+fn is_pinned_accessed() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = Box::pin(AssertUnmoved::new(pending::<()>()));
+    assert!(!future.is_pinned_accessed());
+    assert!(future.pinned_at_location().is_none());
+    let _ = AssertUnmoved::as_inner_pin(future.as_ref());
+    assert!(!future.is_pinned_accessed());
+    assert!(future.pinned_at_location().is_none());
+
+    let line = line!() + 1;
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    assert!(future.is_pinned_accessed());
+    let location = future.pinned_at_location().unwrap();
+    assert_eq!(location.file(), file!());
+    assert_eq!(location.line(), line);
+
+    // The location of the first access is kept.
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(future.pinned_at_location().unwrap().line(), line);
+}
+
+#[test]
+fn clone() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = Box::pin(AssertUnmoved::new(pending::<()>()));
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    let future2 = (*future).clone();
+    // The clone is not tracked, so moving it is fine.
+    let mut future2 = Box::new(future2);
+    assert!(unsafe { Pin::new_unchecked(&mut *future2) }.poll(&mut cx).is_pending());
+    // The original is still tracked.
+    let _ = future.as_mut().get_pin_mut();
+}
+
+#[test]
+fn into_inner() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let future = AssertUnmoved::new(future::ready(1));
+    let mut future = future.into_inner();
+    assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Ready(1));
+
+    let v = AssertUnmoved::new(vec![1, 2]);
+    let v = Box::new(v);
+    assert_eq!(v.into_inner(), [1, 2]);
+}
+
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn into_inner_after_pinned() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = Box::pin(AssertUnmoved::new(pending::<()>()));
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    let future = unsafe { Pin::into_inner_unchecked(future) };
+    let _ = future.into_inner();
+}
+
+#[test]
+fn into_inner_if_unpinned() {
+    let v = AssertUnmoved::new(vec![1, 2]);
+    let v = Box::new(v);
+    assert_eq!(v.into_inner_if_unpinned().unwrap(), [1, 2]);
+
+    let mut future = Box::pin(AssertUnmoved::new(pending::<()>()));
+    let _ = future.as_mut().get_pin_mut();
+    let future = unsafe { Pin::into_inner_unchecked(future) };
+    let mut future = future.into_inner_if_unpinned().unwrap_err();
+    assert!(future.is_pinned_accessed());
+    // `future` moved out of the box, so the move is reported on the next access.
+    assert!(unsafe { Pin::new_unchecked(&mut future) }.try_get_pin_mut().is_err());
+}
+
+#[test]
+fn replace_inner() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = AssertUnmoved::new(future::ready(1));
+    let _ = future.replace_inner(future::ready(2));
+    let mut future = Box::pin(future);
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(2));
+}
+
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn replace_inner_after_pinned() {
+    let mut future = AssertUnmoved::new(pending::<()>());
+    let _ = unsafe { Pin::new_unchecked(&mut future) }.get_pin_mut();
+    let _ = future.replace_inner(pending());
+}
+
+#[test]
+fn map_inner() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let v = AssertUnmoved::with_tag(vec![1, 2], &"v");
+    let v = Box::new(v);
+    let mut future = Box::pin(v.map_inner(|v| future::ready(v.len())));
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(2));
+}
+
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn map_inner_after_pinned() {
+    let mut future = AssertUnmoved::new(pending::<()>());
+    let _ = unsafe { Pin::new_unchecked(&mut future) }.get_pin_mut();
+    let _ = future.map_inner(|_| ());
+}
+
+#[test]
+fn flatten() {
+    let v = AssertUnmoved::new(AssertUnmoved::with_tag(vec![1], &"inner")).flatten();
+    assert_eq!(*v, [1]);
+    let mut v = Box::pin(v);
+    assert_eq!(v.as_mut().get_pin_mut().get_mut().pop(), Some(1));
+
+    let v = AssertUnmoved::with_tag(AssertUnmoved::new(()), &"outer").flatten();
+    let mut v = Box::new(v);
+    let _ = unsafe { Pin::new_unchecked(&mut *v) }.get_pin_mut();
+    let err = unsafe { Pin::new_unchecked(&mut *Box::new(*v)) }.try_get_pin_mut().unwrap_err();
+    assert_eq!(err.tag, Some("outer"));
+}
+
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn flatten_after_pinned() {
+    let mut v = Box::pin(AssertUnmoved::new(AssertUnmoved::new(pending::<()>())));
+    let _ = v.as_mut().get_pin_mut().get_pin_mut();
+    let v = unsafe { Pin::into_inner_unchecked(v) };
+    let _ = v.flatten();
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn into_smart_pointer() {
+    use std::{rc::Rc, sync::Arc};
+
+    let arc: Arc<i32> = AssertUnmoved::new(1).into();
+    assert_eq!(*arc, 1);
+    let rc: Rc<i32> = AssertUnmoved::new(2).into();
+    assert_eq!(*rc, 2);
+    let rc: Rc<Vec<i32>> = AssertUnmoved::new(vec![1, 2, 3]).into();
+    assert_eq!(*rc, [1, 2, 3]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn into_smart_pointer_after_pinned() {
+    use std::sync::Arc;
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = AssertUnmoved::new(pending::<()>());
+    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+    assert!(pinned_future.poll(&mut cx).is_pending());
+
+    let future = Box::new(future);
+    let _: Arc<Pending<()>> = (*future).into();
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn smart_pointer_constructors() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let future = AssertUnmoved::new_box(pending::<()>());
+    assert!(!future.is_pinned_accessed());
+    // Moving the box doesn't move the value.
+    let mut future = Pin::from(future);
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+
+    let mut future = AssertUnmoved::pin_box(pending::<()>());
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    let mut future = future;
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+
+    let rc = AssertUnmoved::pin_rc(1);
+    assert_eq!(**rc, 1);
+    let arc = AssertUnmoved::pin_arc(2);
+    assert_eq!(**arc, 2);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn into_pin_box() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let future = AssertUnmoved::new(pending::<()>());
+    let mut future = future.into_pin_box();
+    assert!(!future.is_pinned_accessed());
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+fn into_pin_box_after_pinned() {
+    let mut future = AssertUnmoved::new(pending::<()>());
+    let _ = unsafe { Pin::new_unchecked(&mut future) }.get_pin_mut();
+    let _ = future.into_pin_box();
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn from_pinned_box() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future: Pin<Box<AssertUnmoved<_>>> = Box::pin(pending::<()>()).into();
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    // Moving the box doesn't move the value.
+    let mut future = future;
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+
+    let x = Pin::<Box<AssertUnmoved<i32>>>::from(Box::pin(1));
+    assert_eq!(**x, 1);
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn get_pin_mut_call_count() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = Box::pin(AssertUnmoved::new(pending::<()>()));
+    assert_eq!(future.get_pin_mut_call_count(), 0);
+    for _ in 0..3 {
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+    }
+    let _ = future.as_mut().get_pin_mut();
+    assert!(future.as_mut().try_get_pin_mut().is_ok());
+    assert_eq!(future.get_pin_mut_call_count(), 5);
+    // Other accesses are not counted.
+    let _ = future.get_ref();
+    let _ = AssertUnmoved::as_inner_pin(future.as_ref());
+    assert_eq!(future.get_pin_mut_call_count(), 5);
+    // Clones start counting from zero.
+    assert_eq!((*future).clone().get_pin_mut_call_count(), 0);
+
+    unsafe { future.as_mut().reset_pin_state() };
+    assert_eq!(future.get_pin_mut_call_count(), 0);
+}
+
+#[test]
+fn reset_pin_state() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = AssertUnmoved::new(pending::<()>());
+    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+    assert!(pinned_future.poll(&mut cx).is_pending());
+    let mut future = Box::new(future);
+    let mut pinned_future = unsafe { Pin::new_unchecked(&mut *future) };
+    assert!(pinned_future.as_mut().try_get_pin_mut().is_err());
+
+    unsafe { pinned_future.as_mut().reset_pin_state() };
+    assert!(!pinned_future.is_pinned_accessed());
+    assert!(pinned_future.pinned_at_location().is_none());
+    // Tracking starts again at the new address.
+    assert!(pinned_future.as_mut().poll(&mut cx).is_pending());
+    assert!(pinned_future.is_pinned_accessed());
+    assert!(pinned_future.as_mut().poll(&mut cx).is_pending());
+}
+
+#[test]
+#[should_panic(expected = "AssertUnmoved moved before drop")]
+fn reset_pin_state_moved() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = AssertUnmoved::new(pending::<()>());
+    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+    assert!(pinned_future.try_get_pin_mut().is_ok());
+    let mut future = Box::new(future);
+    let mut pinned_future = unsafe { Pin::new_unchecked(&mut *future) };
+    unsafe { pinned_future.as_mut().reset_pin_state() };
+    assert!(pinned_future.poll(&mut cx).is_pending());
+    // Moves after the reset are detected.
+    let _future = *future;
+}
+
+#[test]
+fn inspect() {
+    let mut v = Box::pin(AssertUnmoved::new(vec![1, 2]));
+    assert_eq!(AssertUnmoved::inspect(&v, |v| v[1]), 2);
+    assert_eq!(AssertUnmoved::inspect_pin_ref(v.as_ref(), |v| v.len()), 2);
+    assert!(!v.is_pinned_accessed());
+    let _ = v.as_mut().get_pin_mut();
+    // Moving the box doesn't move the `AssertUnmoved`.
+    let v = Box::new(v);
+    assert_eq!(AssertUnmoved::inspect(&v, Vec::len), 2);
+
+    // This doesn't conflict with `Iterator::inspect`.
+    let mut n = 0;
+    assert_eq!(AssertUnmoved::new(1..3).inspect(|_| n += 1).sum::<i32>(), 3);
+    assert_eq!(n, 2);
+}
+
+#[test]
+fn get_addr() {
+    let mut v = AssertUnmoved::new(pending::<()>());
+    assert_eq!(v.get_addr(), &v as *const _ as usize);
+    assert_eq!(v.get_recorded_addr(), None);
+    let _ = unsafe { Pin::new_unchecked(&mut v) }.get_pin_mut();
+    let addr = v.get_addr();
+    assert_eq!(v.get_recorded_addr(), Some(addr));
+
+    let v = Box::new(v);
+    assert_ne!(v.get_addr(), addr);
+    assert_eq!(v.get_recorded_addr(), Some(addr));
+    mem::forget(v);
+}
+
+#[test]
+fn snapshot_addr() {
+    let mut v = Box::pin(AssertUnmoved::new(pending::<()>()));
+    assert!(!v.is_pinned_accessed());
+    v.as_mut().snapshot_addr();
+    assert!(v.is_pinned_accessed());
+    #[cfg(feature = "testing")]
+    assert_eq!(v.get_pin_mut_call_count(), 0);
+    v.as_mut().snapshot_addr();
+    let _ = v.as_mut().get_pin_mut();
+}
+
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn snapshot_addr_moved() {
+    let mut v = AssertUnmoved::new(pending::<()>());
+    unsafe { Pin::new_unchecked(&mut v) }.snapshot_addr();
+    let mut v = Box::new(v);
+    unsafe { Pin::new_unchecked(&mut *v) }.snapshot_addr();
+}
+
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn zst_moved() {
+    let mut x = AssertUnmoved::new(());
+    let _ = unsafe { Pin::new_unchecked(&mut x) }.get_pin_mut();
+    let mut x = Box::new(x);
+    let _ = unsafe { Pin::new_unchecked(&mut *x) }.get_pin_mut();
+}
+
+#[test]
+fn unsized_inner() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future: Pin<Box<AssertUnmoved<dyn Future<Output = i32>>>> =
+        Box::pin(AssertUnmoved::new(future::ready(1)));
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(1));
+    #[cfg(feature = "testing")]
+    assert_eq!(future.get_pin_mut_call_count(), 1);
+
+    let v: Box<AssertUnmoved<[u8]>> = Box::new(AssertUnmoved::new([1, 2, 3]));
+    assert_eq!(v.len(), 3);
+    assert_eq!(**v, [1, 2, 3]);
+}
+
+#[test]
+fn is_same_location_as() {
+    let a = AssertUnmoved::new(());
+    let b = AssertUnmoved::new(());
+    assert!(!a.is_same_location_as(&b));
+    assert!(!b.is_same_location_as(&a));
+
+    let r1 = &a;
+    let r2 = &a;
+    assert!(r1.is_same_location_as(r2));
+    assert!(a.is_same_location_as(&a));
+}
+
+#[test]
+fn polled_from_multiple_threads() {
+    // This shouldn't panic: the future migrates between threads but is not moved.
+    let mut future = Box::pin(AssertUnmoved::new(pending::<()>()));
+    let waker = noop_waker();
+    assert!(future.as_mut().poll(&mut Context::from_waker(&waker)).is_pending());
+    let mut future = thread::spawn(move || {
+        let waker = noop_waker();
+        assert!(future.as_mut().poll(&mut Context::from_waker(&waker)).is_pending());
+        future
+    })
+    .join()
+    .unwrap();
+    assert!(future.as_mut().poll(&mut Context::from_waker(&waker)).is_pending());
+}
+
+#[test]
+fn moved_to_another_thread() {
+    let waker = noop_waker();
+    let mut future = AssertUnmoved::new(pending::<()>());
+    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+    assert!(pinned_future.poll(&mut Context::from_waker(&waker)).is_pending());
+
+    let res = thread::spawn(move || {
+        let mut future = future;
+        let waker = noop_waker();
+        let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+        let _ = pinned_future.poll(&mut Context::from_waker(&waker));
+    })
+    .join();
+    let payload = res.unwrap_err();
+    let msg = payload.downcast_ref::<String>().unwrap();
+    assert!(msg.contains("AssertUnmoved moved between get_pin_mut calls"), "{}", msg);
+}
+
+#[test]
+fn poll_fn() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut inner = AssertUnmoved::new(pending::<()>());
+    // `PollFn` pins the closure structurally, so the captured value is pinned too.
+    let mut future =
+        Box::pin(future::poll_fn(move |cx| unsafe { Pin::new_unchecked(&mut inner) }.poll(cx)));
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    // Moving the box doesn't move the closure or the captured value.
+    let mut future = future;
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+}
+
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn poll_fn_moved() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut inner = AssertUnmoved::new(pending::<()>());
+    let mut future = future::poll_fn(move |cx| unsafe { Pin::new_unchecked(&mut inner) }.poll(cx));
+    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
+    assert!(pinned_future.poll(&mut cx).is_pending());
+
+    // Moving `PollFn` moves the captured value.
+    let mut future = Box::new(future);
+    let pinned_future = unsafe { Pin::new_unchecked(&mut *future) };
+    let _ = pinned_future.poll(&mut cx);
+}
+
+#[test]
+fn in_cursor() {
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(AssertUnmoved::new(vec![1_u8, 2, 3]));
+    cursor.set_position(2);
+    cursor.get_mut().get_mut().extend_from_slice(&[4, 5]);
+    // `Cursor` never pins `AssertUnmoved`, so moving it is fine.
+    let mut cursor = Box::new(cursor);
+    cursor.set_position(cursor.position() + 1);
+    assert_eq!(cursor.position(), 3);
+    assert_eq!(cursor.get_ref()[3..], [4, 5]);
+    assert_eq!(cursor.into_inner().get_ref(), &[1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn range_bounds() {
+    use std::ops::{Bound, RangeBounds as _};
+
+    let range = AssertUnmoved::new(1..3);
+    assert_eq!(range.start_bound(), Bound::Included(&1));
+    assert_eq!(range.end_bound(), Bound::Excluded(&3));
+    assert!(range.contains(&2));
+    assert!(!range.contains(&3));
+
+    // Slice indexing only accepts the concrete range types, so use an API that
+    // is generic over `RangeBounds`.
+    let mut v = vec![0, 1, 2, 3, 4];
+    assert_eq!(v.drain(AssertUnmoved::new(1..3)).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(v, [0, 3, 4]);
+}
+
+#[test]
+fn deref_mut() {
+    let mut v = AssertUnmoved::new(vec![1]);
+    v.push(2);
+    assert_eq!(*v, [1, 2]);
+}
+
+#[test]
+fn as_ref_as_mut() {
+    fn push<V: AsMut<Vec<i32>>>(mut v: V) -> V {
+        v.as_mut().push(1);
+        v
+    }
+
+    let v = push(AssertUnmoved::new(vec![]));
+    assert_eq!(AsRef::<Vec<i32>>::as_ref(&v), &[1]);
+}
+
+#[test]
+fn iterator() {
+    let mut iter = AssertUnmoved::new(vec![1, 2, 3, 4].into_iter());
+    assert_eq!(iter.len(), 4);
+    assert_eq!(iter.next(), Some(1));
+    assert_eq!(iter.next_back(), Some(4));
+    assert_eq!(iter.size_hint(), (2, Some(2)));
+    assert_eq!(iter.rev().collect::<Vec<_>>(), [3, 2]);
+}
+
+#[test]
+fn display() {
+    let v = AssertUnmoved::new(1.5);
+    assert_eq!(v.to_string(), "1.5");
+    assert_eq!(format!("{:>6.2}", v), "  1.50");
+}
+
+#[test]
+fn cmp() {
+    use std::cmp::Ordering;
+
+    let mut a = Box::pin(AssertUnmoved::new(1));
+    let b = AssertUnmoved::new(2);
+    assert_eq!(a.as_mut().get_pin_mut().get_mut(), &mut 1);
+    // Only the underlying values are compared, so a pinned and an unpinned
+    // value at different addresses can be equal.
+    assert_eq!(*a, AssertUnmoved::new(1));
+    assert_ne!(*a, b);
+    assert!(*a < b);
+    assert_eq!((*a).cmp(&b), Ordering::Less);
+    assert_eq!(AssertUnmoved::new(f64::NAN).partial_cmp(&AssertUnmoved::new(0.0)), None);
+
+    let mut v = vec![AssertUnmoved::new(3), AssertUnmoved::new(1), AssertUnmoved::new(2)];
+    v.sort();
+    assert_eq!(v, [AssertUnmoved::new(1), AssertUnmoved::new(2), AssertUnmoved::new(3)]);
+}
+
+#[test]
+fn hash() {
+    use std::{
+        collections::{hash_map::DefaultHasher, HashMap},
+        hash::{Hash, Hasher as _},
+    };
+
+    fn hash<T: Hash>(v: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        v.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let mut pinned = Box::pin(AssertUnmoved::new("a"));
+    let _ = pinned.as_mut().get_pin_mut();
+    assert_eq!(hash(&*pinned), hash(&"a"));
+    assert_eq!(hash(&*pinned), hash(&AssertUnmoved::new("a")));
+
+    let mut map = HashMap::new();
+    map.insert(AssertUnmoved::new("a"), 1);
+    map.insert(AssertUnmoved::new("b"), 2);
+    assert_eq!(map.get(&*pinned), Some(&1));
+    assert_eq!(map[&AssertUnmoved::new("b")], 2);
+}
+
+#[test]
+fn fmt_write() {
+    use std::fmt::Write as _;
+
+    let mut s = AssertUnmoved::new(String::new());
+    s.write_str("a").unwrap();
+    s.write_char('b').unwrap();
+    write!(s, "{}", 1).unwrap();
+    writeln!(s).unwrap();
+    assert_eq!(*s, "ab1\n");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn std_io() {
+    use std::io::{BufRead as _, Cursor, Read as _, Seek as _, SeekFrom, Write as _};
+
+    let mut cursor = AssertUnmoved::new(Cursor::new(Vec::new()));
+    cursor.write_all(b"hello\nworld").unwrap();
+    cursor.flush().unwrap();
+    assert_eq!(cursor.seek(SeekFrom::Start(0)).unwrap(), 0);
+
+    let mut line = String::new();
+    assert_eq!(cursor.read_line(&mut line).unwrap(), 6);
+    assert_eq!(line, "hello\n");
+    assert_eq!(cursor.fill_buf().unwrap(), b"world");
+    cursor.consume(1);
+    let mut buf = [0; 2];
+    cursor.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"or");
+    let mut rest = Vec::new();
+    assert_eq!(cursor.read_to_end(&mut rest).unwrap(), 2);
+    assert_eq!(rest, b"ld");
+
+    cursor.seek(SeekFrom::Start(6)).unwrap();
+    let mut s = String::new();
+    cursor.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "world");
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+fn mutex_take_poll_put_back() {
+    use std::sync::Mutex;
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let slot = Mutex::new(Some(AssertUnmoved::new(pending::<()>())));
+
+    // Take the future out of the mutex and poll it on the stack...
+    let mut future = slot.lock().unwrap().take().unwrap();
+    assert!(unsafe { Pin::new_unchecked(&mut future) }.poll(&mut cx).is_pending());
+    // ...then put it back, which moves it after it has been pinned.
+    *slot.lock().unwrap() = Some(future);
+
+    let mut guard = slot.lock().unwrap();
+    let _ = unsafe { Pin::new_unchecked(guard.as_mut().unwrap()) }.poll(&mut cx);
+}
+
+#[test]
+fn no_check() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    assert_eq!(mem::size_of::<NoCheck<Pending<()>>>(), 0);
+
+    let mut future = NoCheck::new(pending::<()>());
+    assert!(unsafe { Pin::new_unchecked(&mut future) }.poll(&mut cx).is_pending());
+    // This doesn't panic.
+    let mut future = Box::new(future);
+    assert!(unsafe { Pin::new_unchecked(&mut *future) }.poll(&mut cx).is_pending());
+    let _ = future.get_mut();
+    let _: Pending<()> = future.into_inner();
+
+    let mut v = NoCheck::new(vec![1, 2]);
+    v.push(3);
+    assert_eq!(*v.get_ref(), [1, 2, 3]);
+    assert!(!v.is_pinned_accessed());
+    assert_eq!(v.into_inner_if_unpinned().unwrap(), [1, 2, 3]);
+}
+
+// `NoCheck` is meant to be swappable with `AssertUnmoved` via a type alias, so
+// every method added to `AssertUnmoved` must be added here and to `NoCheck`.
+#[test]
+fn no_check_api() {
+    use std::panic::Location;
+
+    macro_rules! api {
+        ($ty:ident) => {{
+            let _: fn(i32) -> $ty<i32> = $ty::new;
+            let _: fn(i32, &'static &'static str) -> $ty<i32> = $ty::with_tag;
+            #[cfg(feature = "alloc")]
+            {
+                use std::{rc::Rc, sync::Arc};
+
+                let _: fn(i32) -> Box<$ty<i32>> = $ty::new_box;
+                let _: fn(i32) -> Pin<Box<$ty<i32>>> = $ty::pin_box;
+                let _: fn(i32) -> Pin<Rc<$ty<i32>>> = $ty::pin_rc;
+                let _: fn(i32) -> Pin<Arc<$ty<i32>>> = $ty::pin_arc;
+                let _: fn($ty<i32>) -> Pin<Box<$ty<i32>>> = $ty::into_pin_box;
+                let _: fn(Pin<Box<i32>>) -> Pin<Box<$ty<i32>>> = From::from;
+                let _: fn($ty<i32>) -> Rc<i32> = From::from;
+                let _: fn($ty<i32>) -> Arc<i32> = From::from;
+            }
+            let _: fn(&$ty<str>) -> &str = $ty::get_ref;
+            let _: fn(&$ty<str>, fn(&str) -> usize) -> usize = $ty::inspect;
+            let _: fn(Pin<&$ty<str>>, fn(Pin<&str>) -> usize) -> usize = $ty::inspect_pin_ref;
+            let _: fn(&mut $ty<str>) -> &mut str = $ty::get_mut;
+            let _: fn(Pin<&mut $ty<str>>) -> Pin<&mut str> = $ty::get_pin_mut;
+            let _: fn(Pin<&mut $ty<str>>) -> Result<Pin<&mut str>, MoveError> =
+                $ty::try_get_pin_mut;
+            let _: fn(Pin<&mut $ty<str>>) = $ty::snapshot_addr;
+            let _: unsafe fn(&mut $ty<Pending<()>>, &mut Context<'_>) -> Poll<()> =
+                $ty::poll_once_unchecked;
+            let _: fn(Pin<&$ty<str>>) -> Pin<&str> = $ty::as_inner_pin;
+            let _: fn(&$ty<str>) -> bool = $ty::is_pinned_accessed;
+            let _: fn(&$ty<str>) -> Option<&'static Location<'static>> = $ty::pinned_at_location;
+            let _: fn(&$ty<str>) -> usize = $ty::get_addr;
+            let _: fn(&$ty<str>) -> Option<usize> = $ty::get_recorded_addr;
+            #[cfg(feature = "testing")]
+            let _: fn(&$ty<str>) -> usize = $ty::get_pin_mut_call_count;
+            let _: unsafe fn(Pin<&mut $ty<str>>) = $ty::reset_pin_state;
+            let _: fn(&$ty<str>, &$ty<str>) -> bool = $ty::is_same_location_as;
+            let _: fn($ty<i32>) -> i32 = $ty::into_inner;
+            let _: fn($ty<i32>) -> Result<i32, $ty<i32>> = $ty::into_inner_if_unpinned;
+            let _: fn(&mut $ty<i32>, i32) -> i32 = $ty::replace_inner;
+            let _: fn($ty<i32>, fn(i32) -> u8) -> $ty<u8> = $ty::map_inner;
+            let _: fn($ty<$ty<i32>>) -> $ty<i32> = $ty::flatten;
+            let _: fn(i32) -> $ty<i32> = From::from;
+        }};
+    }
+    api!(AssertUnmoved);
+    api!(NoCheck);
+}
+
+#[cfg(feature = "futures03")]
+mod futures03 {
+    use std::{
+        future::Future as _,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use assert_unmoved::*;
+    use futures::{
+        future,
+        io::{self, AsyncBufRead as _, AsyncSeekExt as _, BufReader, Cursor, SeekFrom},
+        stream::{self, FusedStream as _, Stream as _},
+        task::noop_waker,
+    };
+    use futures_sink::Sink;
+
+    use crate::block_on;
+
+    #[derive(Default)]
+    struct VecSink(Vec<i32>);
+
+    impl Sink<i32> for VecSink {
+        type Error = ();
+
+        fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(mut self: Pin<&mut Self>, item: i32) -> Result<(), ()> {
+            self.0.push(item);
+            Ok(())
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn sink() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut sink = Box::pin(AssertUnmoved::new(VecSink::default()));
+        assert_eq!(sink.as_mut().poll_ready(&mut cx), Poll::Ready(Ok(())));
+        sink.as_mut().start_send(1).unwrap();
+        assert_eq!(sink.as_mut().poll_flush(&mut cx), Poll::Ready(Ok(())));
+        assert_eq!(sink.0, [1]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "AssertUnmoved moved between get_pin_mut calls (detected in Sink::start_send)"
+    )]
+    fn moved_between_poll_ready_and_start_send() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut sink = AssertUnmoved::new(VecSink::default());
+        let pinned_sink = unsafe { Pin::new_unchecked(&mut sink) };
+        assert_eq!(pinned_sink.poll_ready(&mut cx), Poll::Ready(Ok(())));
+
+        let mut sink = Box::new(sink);
+        let pinned_sink = unsafe { Pin::new_unchecked(&mut *sink) };
+        let _ = pinned_sink.start_send(1);
+    }
+
+    #[test]
+    fn copy() {
+        let mut reader = Box::pin(AssertUnmoved::new(Cursor::new(vec![1, 2, 3])));
+        let mut writer = Box::pin(AssertUnmoved::new(vec![]));
+        assert_eq!(block_on(io::copy(reader.as_mut(), &mut writer.as_mut())).unwrap(), 3);
+        assert_eq!(**writer, [1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+    fn copy_moved_reader() {
+        let mut reader = AssertUnmoved::new(Cursor::new(vec![1, 2, 3]));
+        let mut writer = Box::pin(AssertUnmoved::new(vec![]));
+        let pinned_reader = unsafe { Pin::new_unchecked(&mut reader) };
+        block_on(io::copy(pinned_reader, &mut writer.as_mut())).unwrap();
+
+        let mut reader = Box::new(reader);
+        let pinned_reader = unsafe { Pin::new_unchecked(&mut *reader) };
+        let _ = block_on(io::copy(pinned_reader, &mut writer.as_mut()));
+    }
+
+    #[test]
+    #[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+    fn copy_moved_writer() {
+        let mut reader = Box::pin(AssertUnmoved::new(Cursor::new(vec![1, 2, 3])));
+        let mut writer = AssertUnmoved::new(vec![]);
+        let mut pinned_writer = unsafe { Pin::new_unchecked(&mut writer) };
+        block_on(io::copy(reader.as_mut(), &mut pinned_writer)).unwrap();
+
+        reader.set(AssertUnmoved::new(Cursor::new(vec![4, 5, 6])));
+        let mut writer = Box::new(writer);
+        let mut pinned_writer = unsafe { Pin::new_unchecked(&mut *writer) };
+        let _ = block_on(io::copy(reader.as_mut(), &mut pinned_writer));
+    }
+
+    #[test]
+    fn seek() {
+        let mut cursor = Box::pin(AssertUnmoved::new(Cursor::new(vec![1, 2, 3, 4])));
+        assert_eq!(block_on(cursor.seek(SeekFrom::Start(1))).unwrap(), 1);
+        assert_eq!(cursor.position(), 1);
+        assert_eq!(block_on(cursor.seek(SeekFrom::Current(2))).unwrap(), 3);
+        assert_eq!(cursor.position(), 3);
+        assert_eq!(block_on(cursor.seek(SeekFrom::Current(-1))).unwrap(), 2);
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(block_on(cursor.seek(SeekFrom::End(-4))).unwrap(), 0);
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(block_on(cursor.seek(SeekFrom::End(0))).unwrap(), 4);
+        assert_eq!(cursor.position(), 4);
+    }
+
+    #[test]
+    fn seek_out_of_bounds() {
+        let mut cursor = Box::pin(AssertUnmoved::new(Cursor::new(vec![1, 2, 3, 4])));
+        // Seeking past the end is allowed.
+        assert_eq!(block_on(cursor.seek(SeekFrom::End(2))).unwrap(), 6);
+        assert_eq!(cursor.position(), 6);
+        // Seeking before the start is an error, which is passed through as is.
+        assert_eq!(
+            block_on(cursor.seek(SeekFrom::Current(-7))).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(cursor.position(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+    fn seek_moved() {
+        let mut cursor = AssertUnmoved::new(Cursor::new(vec![1, 2, 3, 4]));
+        let mut pinned_cursor = unsafe { Pin::new_unchecked(&mut cursor) };
+        assert_eq!(block_on(pinned_cursor.seek(SeekFrom::Start(1))).unwrap(), 1);
+
+        let mut cursor = Box::new(cursor);
+        let mut pinned_cursor = unsafe { Pin::new_unchecked(&mut *cursor) };
+        let _ = block_on(pinned_cursor.seek(SeekFrom::Current(1)));
+    }
+
+    #[test]
+    fn fused_future_moved() {
+        use futures::future::{FusedFuture as _, FutureExt as _};
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut future = AssertUnmoved::new(future::pending::<()>().fuse());
+        assert!(unsafe { Pin::new_unchecked(&mut future) }.poll(&mut cx).is_pending());
+        let mut future = Box::new(future);
+        // `is_terminated` takes `&self`, so it does not detect the move...
+        assert!(!future.is_terminated());
+        // ...but the next mutable access does.
+        assert!(unsafe { Pin::new_unchecked(&mut *future) }.try_get_pin_mut().is_err());
+    }
+
+    #[test]
+    fn stream_size_hint_moved() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut stream = AssertUnmoved::new(stream::iter(vec![1, 2, 3]));
+        assert_eq!(
+            unsafe { Pin::new_unchecked(&mut stream) }.poll_next(&mut cx),
+            Poll::Ready(Some(1))
+        );
+        let mut stream = Box::new(stream);
+        // `size_hint` takes `&self`, so it does not detect the move...
+        assert_eq!(stream.size_hint(), (2, Some(2)));
+        // ...but the next mutable access does.
+        assert!(unsafe { Pin::new_unchecked(&mut *stream) }.try_get_pin_mut().is_err());
+    }
+
+    #[test]
+    fn fused_stream_empty() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut stream = Box::pin(AssertUnmoved::new(stream::empty::<()>()));
+        assert!(stream.is_terminated());
+        assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(None));
+        assert!(stream.is_terminated());
+        assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(None));
+    }
+
+    #[test]
+    fn fused_stream_once() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut stream = Box::pin(AssertUnmoved::new(stream::once(future::ready(1))));
+        assert!(!stream.is_terminated());
+        assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(Some(1)));
+        assert!(stream.is_terminated());
+        assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(None));
+        assert!(stream.is_terminated());
+    }
+
+    #[test]
+    fn fill_buf_lifetime() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut reader =
+            Box::pin(AssertUnmoved::new(BufReader::with_capacity(2, Cursor::new(vec![1, 2, 3]))));
+        let mut other = Box::pin(AssertUnmoved::new(future::pending::<()>()));
+
+        // The returned buffer borrows from `reader`, not from a temporary, so
+        // it can be held while other futures are polled.
+        let buf = match reader.as_mut().poll_fill_buf(&mut cx) {
+            Poll::Ready(Ok(buf)) => buf,
+            res => panic!("{:?}", res),
+        };
+        assert!(other.as_mut().poll(&mut cx).is_pending());
+        assert!(other.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(buf, [1, 2]);
+        let amt = buf.len();
+        reader.as_mut().consume(amt);
+
+        let buf = match reader.as_mut().poll_fill_buf(&mut cx) {
+            Poll::Ready(Ok(buf)) => buf,
+            res => panic!("{:?}", res),
+        };
+        assert!(other.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(buf, [3]);
+    }
+    #[test]
+    fn buf_read_ext() {
+        use futures::{io::AsyncBufReadExt as _, stream::TryStreamExt as _};
+
+        let reader = AssertUnmoved::new(Cursor::new(b"a\nbc\n\nd".to_vec()));
+        let lines = block_on(reader.lines().try_collect::<Vec<_>>()).unwrap();
+        assert_eq!(lines, ["a", "bc", "", "d"]);
+
+        let mut reader = Box::pin(AssertUnmoved::new(Cursor::new(b"a\nbc".to_vec())));
+        let mut line = String::new();
+        assert_eq!(block_on(reader.read_line(&mut line)).unwrap(), 2);
+        assert_eq!(line, "a\n");
+        let mut buf = Vec::new();
+        assert_eq!(block_on(reader.read_until(b'c', &mut buf)).unwrap(), 2);
+        assert_eq!(buf, b"bc");
+    }
+}
+
+#[cfg(feature = "tokio02")]
+mod tokio02 {
+    use std::{
+        cell::Cell,
+        io::{self, IoSlice},
+        mem::MaybeUninit,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use assert_unmoved::*;
+    use bytes05::{buf::BufExt, Buf, BufMut as _};
+    use futures::task::noop_waker;
+    use tokio02_crate::io::{AsyncRead, AsyncReadExt as _, AsyncWrite};
+
+    use crate::block_on;
+
+    struct Reader {
+        data: Vec<u8>,
+        zeroes_buffer: bool,
+        prepared: Cell<usize>,
+    }
+
+    impl Reader {
+        fn new(data: Vec<u8>, zeroes_buffer: bool) -> Self {
+            Self { data, zeroes_buffer, prepared: Cell::new(0) }
+        }
+    }
+
+    impl AsyncRead for Reader {
+        unsafe fn prepare_uninitialized_buffer(&self, buf: &mut [MaybeUninit<u8>]) -> bool {
+            self.prepared.set(self.prepared.get() + 1);
+            if self.zeroes_buffer {
+                for x in buf {
+                    *x = MaybeUninit::new(0);
+                }
+            }
+            self.zeroes_buffer
+        }
+
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            // Only writes to the buffer, so returning `false` from
+            // `prepare_uninitialized_buffer` is fine.
+            let n = buf.len().min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data.drain(..n);
+            Poll::Ready(Ok(n))
+        }
+    }
+
+    #[test]
+    fn prepare_uninitialized_buffer() {
+        for &zeroes_buffer in &[false, true] {
+            let reader = AssertUnmoved::new(Reader::new(vec![], zeroes_buffer));
+            let mut buf = [MaybeUninit::new(1_u8); 4];
+            let initialized = unsafe { reader.prepare_uninitialized_buffer(&mut buf) };
+            assert_eq!(initialized, zeroes_buffer);
+            assert_eq!(reader.prepared.get(), 1);
+            let buf = buf.iter().map(|x| unsafe { x.assume_init() }).collect::<Vec<_>>();
+            if zeroes_buffer {
+                assert_eq!(buf, [0; 4]);
+            } else {
+                // Returning `false` means the underlying reader did not touch the buffer.
+                assert_eq!(buf, [1; 4]);
+            }
+        }
+    }
+
+    #[test]
+    fn read_to_end() {
+        let mut reader = Box::pin(AssertUnmoved::new(Reader::new(vec![1, 2, 3], true)));
+        let mut buf = vec![];
+        assert_eq!(block_on(reader.read_to_end(&mut buf)).unwrap(), 3);
+        assert_eq!(buf, [1, 2, 3]);
+        // The runtime asks the reader to prepare the buffer through `AssertUnmoved`.
+        assert!(reader.prepared.get() > 0);
+    }
+
+    #[derive(Default)]
+    struct VectoredWriter {
+        data: Vec<u8>,
+        write_buf_calls: usize,
+    }
+
+    impl AsyncWrite for VectoredWriter {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.data.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_write_buf<B: Buf>(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut B,
+        ) -> Poll<io::Result<usize>> {
+            self.write_buf_calls += 1;
+            let mut slices = [IoSlice::new(&[]); 4];
+            let n = buf.bytes_vectored(&mut slices);
+            let mut len = 0;
+            for slice in &slices[..n] {
+                self.data.put_slice(slice);
+                len += slice.len();
+            }
+            buf.advance(len);
+            Poll::Ready(Ok(len))
+        }
+    }
+
+    // tokio 0.2's AsyncWrite has no poll_write_vectored; vectored writes go
+    // through poll_write_buf.
+    #[test]
+    fn write_buf_vectored() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut writer = Box::pin(AssertUnmoved::new(VectoredWriter::default()));
+        let mut buf = BufExt::chain(&b"ab"[..], &b"cd"[..]);
+        match writer.as_mut().poll_write_buf(&mut cx, &mut buf) {
+            Poll::Ready(Ok(n)) => assert_eq!(n, 4),
+            res => panic!("{:?}", res),
+        }
+        assert!(!buf.has_remaining());
+        assert_eq!(writer.data, b"abcd");
+        assert_eq!(writer.write_buf_calls, 1);
+    }
+}
+
+#[cfg(feature = "tokio03")]
+mod tokio03 {
+    use std::{
+        io::{self, Cursor},
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use assert_unmoved::*;
+    use futures::task::noop_waker;
+    use tokio03_crate::io::{AsyncBufRead, AsyncWrite as _, BufReader};
+
+    #[track_caller]
+    fn assert_fill_buf<R: AsyncBufRead>(
+        reader: Pin<&mut R>,
+        cx: &mut Context<'_>,
+        expected: &[u8],
+    ) {
+        match reader.poll_fill_buf(cx) {
+            Poll::Ready(Ok(buf)) => assert_eq!(buf, expected),
+            res => panic!("{:?}", res),
+        }
+    }
+
+    #[test]
+    fn buf_read() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut reader = Box::pin(AssertUnmoved::new(Cursor::new(vec![1, 2, 3])));
+        assert_fill_buf(reader.as_mut(), &mut cx, &[1, 2, 3]);
+        reader.as_mut().consume(2);
+        assert_fill_buf(reader.as_mut(), &mut cx, &[3]);
+        reader.as_mut().consume(1);
+        assert_fill_buf(reader.as_mut(), &mut cx, &[]);
+    }
+
+    #[test]
+    fn buf_reader() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut reader = Box::pin(BufReader::new(AssertUnmoved::new(Cursor::new(vec![1, 2, 3]))));
+        assert_fill_buf(reader.as_mut(), &mut cx, &[1, 2, 3]);
+        reader.as_mut().consume(1);
+        assert_fill_buf(reader.as_mut(), &mut cx, &[2, 3]);
+    }
+
+    #[test]
+    fn write_vectored() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut writer = Box::pin(AssertUnmoved::new(Cursor::new(vec![])));
+        assert!(writer.is_write_vectored());
+        let bufs = [io::IoSlice::new(b"ab"), io::IoSlice::new(b"c")];
+        match writer.as_mut().poll_write_vectored(&mut cx, &bufs) {
+            Poll::Ready(Ok(n)) => assert_eq!(n, 3),
+            res => panic!("{:?}", res),
+        }
+        assert_eq!(writer.get_ref().get_ref(), b"abc");
+
+        let mut writer = Box::pin(NoCheck::new(Cursor::new(vec![])));
+        assert!(writer.is_write_vectored());
+        match writer.as_mut().poll_write_vectored(&mut cx, &bufs) {
+            Poll::Ready(Ok(n)) => assert_eq!(n, 3),
+            res => panic!("{:?}", res),
+        }
+        assert_eq!(writer.get_ref().get_ref(), b"abc");
+    }
+}
+
+#[cfg(feature = "tokio1")]
+mod tokio1 {
+    use std::{
+        io,
+        marker::PhantomPinned,
+        pin::Pin,
+        ptr, slice,
+        task::{Context, Poll},
+    };
+
+    use assert_unmoved::*;
+    use futures::{stream, task::noop_waker};
+    use tokio1_crate::io::{
+        self as tokio_io, AsyncRead, AsyncWrite, AsyncWriteExt as _, BufReader, ReadBuf,
+    };
+    use tokio_util07_crate::io::StreamReader;
+
+    use crate::block_on;
+
+    /// Returns `Pending` and then fills at most 2 bytes, alternately.
+    struct PartialReader {
+        data: &'static [u8],
+        pending: bool,
+    }
+
+    impl PartialReader {
+        fn new(data: &'static [u8]) -> Self {
+            Self { data, pending: true }
+        }
+    }
+
+    impl AsyncRead for PartialReader {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            if self.pending {
+                self.pending = false;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            self.pending = true;
+            let n = self.data.len().min(buf.remaining()).min(2);
+            buf.put_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn partial_read() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut reader = Box::pin(AssertUnmoved::new(PartialReader::new(b"abcd")));
+        let mut buf = [0; 4];
+        let mut buf = ReadBuf::new(&mut buf);
+        assert!(reader.as_mut().poll_read(&mut cx, &mut buf).is_pending());
+        assert!(buf.filled().is_empty());
+        assert!(reader.as_mut().poll_read(&mut cx, &mut buf).is_ready());
+        assert_eq!(buf.filled(), b"ab");
+        assert!(reader.as_mut().poll_read(&mut cx, &mut buf).is_pending());
+        assert_eq!(buf.filled(), b"ab");
+        assert!(reader.as_mut().poll_read(&mut cx, &mut buf).is_ready());
+        assert_eq!(buf.filled(), b"abcd");
+    }
+
+    #[test]
+    #[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+    fn moved_between_partial_reads() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut buf = [0; 4];
+        let mut buf = ReadBuf::new(&mut buf);
+        let mut reader = AssertUnmoved::new(PartialReader::new(b"abcd"));
+        let pinned_reader = unsafe { Pin::new_unchecked(&mut reader) };
+        assert!(pinned_reader.poll_read(&mut cx, &mut buf).is_pending());
+
+        let mut reader = Box::new(reader);
+        let pinned_reader = unsafe { Pin::new_unchecked(&mut *reader) };
+        let _ = pinned_reader.poll_read(&mut cx, &mut buf);
+    }
+
+    /// Fills at most 2 bytes from its own buffer, through a pointer to the
+    /// buffer that is stored on the first read.
+    struct SelfRefReader {
+        data: [u8; 4],
+        cursor: *const u8,
+        pos: usize,
+        _pinned: PhantomPinned,
+    }
+
+    impl SelfRefReader {
+        fn new(data: [u8; 4]) -> Self {
+            Self { data, cursor: ptr::null(), pos: 0, _pinned: PhantomPinned }
+        }
+    }
+
+    impl AsyncRead for SelfRefReader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let this = unsafe { self.get_unchecked_mut() };
+            if this.cursor.is_null() {
+                this.cursor = this.data.as_ptr();
+            }
+            // The stored pointer is only valid if the reader has not moved.
+            assert_eq!(this.cursor, this.data.as_ptr());
+            let n = (this.data.len() - this.pos).min(buf.remaining()).min(2);
+            buf.put_slice(unsafe { slice::from_raw_parts(this.cursor.add(this.pos), n) });
+            this.pos += n;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn self_referential_partial_reads() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut reader = Box::pin(AssertUnmoved::new(SelfRefReader::new(*b"abcd")));
+        let mut buf = [0; 8];
+        let mut buf = ReadBuf::new(&mut buf);
+        assert!(reader.as_mut().poll_read(&mut cx, &mut buf).is_ready());
+        assert_eq!(buf.filled(), b"ab");
+        assert!(reader.as_mut().poll_read(&mut cx, &mut buf).is_ready());
+        assert_eq!(buf.filled(), b"abcd");
+        assert!(reader.as_mut().poll_read(&mut cx, &mut buf).is_ready());
+        assert_eq!(buf.filled(), b"abcd");
+    }
+
+    #[test]
+    #[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+    fn self_referential_moved() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut buf = [0; 8];
+        let mut buf = ReadBuf::new(&mut buf);
+        let mut reader = AssertUnmoved::new(SelfRefReader::new(*b"abcd"));
+        let pinned_reader = unsafe { Pin::new_unchecked(&mut reader) };
+        assert!(pinned_reader.poll_read(&mut cx, &mut buf).is_ready());
+
+        // The move is detected before the stale pointer in the reader is used.
+        let mut reader = Box::new(reader);
+        let pinned_reader = unsafe { Pin::new_unchecked(&mut *reader) };
+        let _ = pinned_reader.poll_read(&mut cx, &mut buf);
+    }
+
+    #[test]
+    fn copy() {
+        let chunks = vec![Ok::<_, io::Error>(&b"ab"[..]), Ok(&b"c"[..])];
+        let mut reader =
+            Box::pin(AssertUnmoved::new(BufReader::new(StreamReader::new(stream::iter(chunks)))));
+        let mut writer = Box::pin(AssertUnmoved::new(vec![]));
+        assert_eq!(
+            block_on(tokio_io::copy(&mut reader.as_mut(), &mut writer.as_mut())).unwrap(),
+            3
+        );
+        assert_eq!(**writer, *b"abc");
+    }
+
+    #[test]
+    #[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+    fn copy_moved_reader() {
+        let chunks = vec![Ok::<_, io::Error>(&b"ab"[..]), Ok(&b"c"[..])];
+        let mut reader =
+            AssertUnmoved::new(BufReader::new(StreamReader::new(stream::iter(chunks))));
+        let mut writer = Box::pin(AssertUnmoved::new(vec![]));
+        let mut pinned_reader = unsafe { Pin::new_unchecked(&mut reader) };
+        block_on(tokio_io::copy(&mut pinned_reader, &mut writer.as_mut())).unwrap();
+
+        let mut reader = Box::new(reader);
+        let mut pinned_reader = unsafe { Pin::new_unchecked(&mut *reader) };
+        let _ = block_on(tokio_io::copy(&mut pinned_reader, &mut writer.as_mut()));
+    }
+
+    /// Records which of `poll_write` and `poll_write_vectored` was called.
+    #[derive(Default)]
+    struct VectoredWriter {
+        vectored: bool,
+        write_calls: usize,
+        write_vectored_calls: usize,
+        buf: Vec<u8>,
+    }
+
+    impl AsyncWrite for VectoredWriter {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.write_calls += 1;
+            self.buf.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_write_vectored(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            bufs: &[io::IoSlice<'_>],
+        ) -> Poll<io::Result<usize>> {
+            self.write_vectored_calls += 1;
+            let mut n = 0;
+            for buf in bufs {
+                self.buf.extend_from_slice(buf);
+                n += buf.len();
+            }
+            Poll::Ready(Ok(n))
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.vectored
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn write_vectored() {
+        for vectored in [false, true] {
+            let mut writer =
+                Box::pin(AssertUnmoved::new(VectoredWriter { vectored, ..Default::default() }));
+            assert_eq!(writer.is_write_vectored(), vectored);
+
+            let bufs = [io::IoSlice::new(b"ab"), io::IoSlice::new(b"c")];
+            assert_eq!(block_on(writer.write_vectored(&bufs)).unwrap(), 3);
+            assert_eq!(writer.write_calls, 0);
+            assert_eq!(writer.write_vectored_calls, 1);
+
+            // `write_all_buf` takes the vectored path only if the writer
+            // prefers it.
+            let mut buf = &b"de"[..];
+            block_on(writer.write_all_buf(&mut buf)).unwrap();
+            assert_eq!(writer.write_calls, usize::from(!vectored));
+            assert_eq!(writer.write_vectored_calls, 1 + usize::from(vectored));
+            assert_eq!(writer.buf, b"abcde");
+        }
+    }
+
+    #[cfg(feature = "futures03")]
+    #[test]
+    fn receiver_stream() {
+        use tokio1_crate::sync::mpsc;
+        use tokio_stream::{wrappers::ReceiverStream, StreamExt as _};
+
+        let (tx, rx) = mpsc::channel(3);
+        for i in 0..3 {
+            tx.try_send(i).unwrap();
+        }
+        drop(tx);
+
+        let mut stream = Box::pin(AssertUnmoved::new(ReceiverStream::new(rx)));
+        assert_eq!(block_on(stream.next()), Some(0));
+        assert_eq!(block_on(stream.as_mut().map(|i| i * 2).collect::<Vec<_>>()), [2, 4]);
+        assert_eq!(block_on(stream.next()), None);
+    }
+
+    #[cfg(feature = "futures03")]
+    #[test]
+    #[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+    fn receiver_stream_moved() {
+        use tokio1_crate::sync::mpsc;
+        use tokio_stream::{wrappers::ReceiverStream, StreamExt as _};
+
+        let (tx, rx) = mpsc::channel(2);
+        tx.try_send(0).unwrap();
+        tx.try_send(1).unwrap();
+
+        let mut stream = AssertUnmoved::new(ReceiverStream::new(rx));
+        assert_eq!(block_on(unsafe { Pin::new_unchecked(&mut stream) }.next()), Some(0));
+        let mut stream = Box::new(stream);
+        let _ = block_on(unsafe { Pin::new_unchecked(&mut *stream) }.next());
+    }
 }
 
-#[test]
-fn do_not_panic_when_not_polled() {
-    // This shouldn't panic.
-    let future = AssertUnmoved::new(pending::<()>());
-    drop(future);
+#[cfg(feature = "bytes1")]
+mod bytes1 {
+    use assert_unmoved::*;
+    use bytes1_crate::{Buf as _, BufMut as _, Bytes};
+
+    #[test]
+    fn buf() {
+        let mut buf = AssertUnmoved::new(Bytes::from_static(b"hello"));
+        assert_eq!(buf.remaining(), 5);
+        assert_eq!(buf.get_u8(), b'h');
+        assert_eq!(buf.copy_to_bytes(2), Bytes::from_static(b"el"));
+        assert_eq!(buf.chunk(), b"lo");
+    }
+
+    #[test]
+    fn buf_mut() {
+        let mut buf = AssertUnmoved::new(Vec::new());
+        buf.put_u8(1);
+        buf.put_slice(&[2, 3]);
+        buf.put_u16(0x0405);
+        assert_eq!(buf.get_ref(), &[1, 2, 3, 4, 5]);
+    }
 }
 
-#[test]
-#[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
-fn do_not_double_panic() {
-    // This test should only panic, not abort the process.
-    let waker = noop_waker();
-    let mut cx = Context::from_waker(&waker);
+#[cfg(feature = "tokio-util07")]
+mod tokio_util07 {
+    use std::{convert::TryFrom as _, io};
 
-    // First we allocate the future on the stack and poll it.
-    let mut future = AssertUnmoved::new(pending::<()>());
-    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
-    assert!(pinned_future.poll(&mut cx).is_pending());
+    use assert_unmoved::*;
+    use bytes1_crate::{Buf as _, BufMut as _, BytesMut};
+    use tokio_util07_crate::codec::{Decoder, Encoder};
 
-    // Next we move it back to the heap and poll it again. This second call
-    // should panic (as the future is moved), but we shouldn't panic again
-    // whilst dropping `AssertUnmoved`.
-    let mut future = Box::new(future);
-    let pinned_boxed_future = unsafe { Pin::new_unchecked(&mut *future) };
-    assert!(pinned_boxed_future.poll(&mut cx).is_pending());
+    /// Frames each message as a one-byte length followed by the bytes.
+    struct LengthCodec;
+
+    impl Encoder<&[u8]> for LengthCodec {
+        type Error = io::Error;
+
+        fn encode(&mut self, item: &[u8], dst: &mut BytesMut) -> io::Result<()> {
+            dst.put_u8(u8::try_from(item.len()).unwrap());
+            dst.put_slice(item);
+            Ok(())
+        }
+    }
+
+    impl Decoder for LengthCodec {
+        type Item = Vec<u8>;
+        type Error = io::Error;
+
+        fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<Vec<u8>>> {
+            match src.first() {
+                Some(&len) if src.len() > usize::from(len) => {
+                    src.advance(1);
+                    Ok(Some(src.split_to(len.into()).to_vec()))
+                }
+                _ => Ok(None),
+            }
+        }
+    }
+
+    #[test]
+    fn codec() {
+        let mut codec = AssertUnmoved::new(LengthCodec);
+        let mut buf = BytesMut::new();
+        codec.encode(b"ab", &mut buf).unwrap();
+        codec.encode(b"c", &mut buf).unwrap();
+        assert_eq!(&buf[..], b"\x02ab\x01c");
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(b"ab".to_vec()));
+        assert_eq!(codec.decode_eof(&mut buf).unwrap(), Some(b"c".to_vec()));
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+    }
 }
 
-#[test]
-#[should_panic(expected = "AssertUnmoved moved before drop")]
-fn moved_before_drop() {
-    struct Test<T>(Option<T>);
+#[cfg(feature = "tower-service03")]
+mod tower_service03 {
+    use std::task::{Context, Poll};
 
-    impl<T> Drop for Test<T> {
-        fn drop(&mut self) {
-            // This moves `T`.
-            self.0.take();
+    use assert_unmoved::*;
+    use futures::{
+        future::{self, Ready},
+        task::noop_waker,
+    };
+    use tower_service::Service;
+
+    struct Counter(usize);
+
+    impl Service<usize> for Counter {
+        type Response = usize;
+        type Error = ();
+        type Future = Ready<Result<usize, ()>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, n: usize) -> Self::Future {
+            self.0 += n;
+            future::ready(Ok(self.0))
         }
     }
 
-    let mut x = Test(Some(AssertUnmoved::new(pending::<()>())));
-    let x = unsafe { Pin::new_unchecked(&mut x) };
-    // This `map_unchecked_mut` is unsound because `Test`'s destructor moves `T`.
-    let _ = unsafe { x.map_unchecked_mut(|x| &mut x.0) }.as_pin_mut().unwrap().get_pin_mut();
+    #[test]
+    fn call() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut service = AssertUnmoved::new(Counter(0));
+        assert_eq!(service.poll_ready(&mut cx), Poll::Ready(Ok(())));
+        assert_eq!(service.call(1).into_inner(), Ok(1));
+        assert_eq!(service.poll_ready(&mut cx), Poll::Ready(Ok(())));
+        assert_eq!(service.call(2).into_inner(), Ok(3));
+    }
 }
 
-#[test]
-#[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
-fn misuse_get_mut() {
-    let waker = noop_waker();
-    let mut cx = Context::from_waker(&waker);
+#[cfg(feature = "http-body1")]
+mod http_body1 {
+    use std::{
+        convert::Infallible,
+        pin::Pin,
+        task::{Context, Poll},
+    };
 
-    let mut future = AssertUnmoved::new(pending::<()>());
-    let pinned_future = unsafe { Pin::new_unchecked(&mut future) };
-    assert!(pinned_future.poll(&mut cx).is_pending());
+    use assert_unmoved::*;
+    use bytes1_crate::Bytes;
+    use futures::task::noop_waker;
+    use http_body::{Body, Frame, SizeHint};
 
-    let mut future = Box::new(future);
-    let _ = future.get_mut();
+    struct Chunks(Vec<&'static str>);
+
+    impl Body for Chunks {
+        type Data = Bytes;
+        type Error = Infallible;
+
+        fn poll_frame(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+            if self.0.is_empty() {
+                Poll::Ready(None)
+            } else {
+                Poll::Ready(Some(Ok(Frame::data(Bytes::from(self.0.remove(0))))))
+            }
+        }
+
+        fn is_end_stream(&self) -> bool {
+            self.0.is_empty()
+        }
+
+        fn size_hint(&self) -> SizeHint {
+            SizeHint::with_exact(self.0.iter().map(|s| s.len() as u64).sum())
+        }
+    }
+
+    #[test]
+    fn poll_frame() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut body = Box::pin(AssertUnmoved::new(Chunks(vec!["ab", "c"])));
+        assert!(!body.is_end_stream());
+        assert_eq!(body.size_hint().exact(), Some(3));
+        let frame = match body.as_mut().poll_frame(&mut cx) {
+            Poll::Ready(Some(Ok(frame))) => frame,
+            _ => unreachable!(),
+        };
+        assert_eq!(frame.into_data().unwrap(), "ab");
+        assert!(body.as_mut().poll_frame(&mut cx).is_ready());
+        assert!(body.is_end_stream());
+        assert!(matches!(body.as_mut().poll_frame(&mut cx), Poll::Ready(None)));
+    }
+
+    #[test]
+    #[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+    fn poll_frame_moved() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut body = AssertUnmoved::new(Chunks(vec!["a", "b"]));
+        let _ = unsafe { Pin::new_unchecked(&mut body) }.poll_frame(&mut cx);
+        let mut body = Box::new(body);
+        let _ = unsafe { Pin::new_unchecked(&mut *body) }.poll_frame(&mut cx);
+    }
+}
+
+#[cfg(feature = "embedded-hal-async1")]
+mod embedded_hal_async1 {
+    use std::convert::Infallible;
+
+    use assert_unmoved::*;
+    use embedded_hal_async::{
+        i2c::{self, I2c},
+        spi::{self, SpiBus},
+    };
+
+    use crate::block_on;
+
+    /// Echoes the last written bytes on read.
+    #[derive(Default)]
+    struct SpiLoopback(Vec<u8>);
+
+    impl spi::ErrorType for SpiLoopback {
+        type Error = Infallible;
+    }
+
+    impl SpiBus for SpiLoopback {
+        async fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+            words.copy_from_slice(&self.0[..words.len()]);
+            Ok(())
+        }
+
+        async fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+            self.0 = words.to_vec();
+            Ok(())
+        }
+
+        async fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Infallible> {
+            self.read(read).await?;
+            self.write(write).await
+        }
+
+        async fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+            let write = words.to_vec();
+            self.transfer(words, &write).await
+        }
+
+        async fn flush(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+    }
+
+    /// Echoes the last written bytes on read.
+    #[derive(Default)]
+    struct I2cLoopback(Vec<u8>);
+
+    impl i2c::ErrorType for I2cLoopback {
+        type Error = Infallible;
+    }
+
+    impl I2c for I2cLoopback {
+        async fn transaction(
+            &mut self,
+            _: u8,
+            operations: &mut [i2c::Operation<'_>],
+        ) -> Result<(), Infallible> {
+            for op in operations {
+                match op {
+                    i2c::Operation::Read(buf) => buf.copy_from_slice(&self.0[..buf.len()]),
+                    i2c::Operation::Write(buf) => self.0 = buf.to_vec(),
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn spi_bus() {
+        let mut bus = AssertUnmoved::new(SpiLoopback::default());
+        block_on(bus.write(&[1, 2])).unwrap();
+        let mut buf = [0; 2];
+        block_on(bus.transfer(&mut buf, &[3, 4])).unwrap();
+        assert_eq!(buf, [1, 2]);
+        block_on(bus.transfer_in_place(&mut buf)).unwrap();
+        assert_eq!(buf, [3, 4]);
+        block_on(bus.flush()).unwrap();
+    }
+
+    #[test]
+    fn i2c() {
+        let mut i2c = AssertUnmoved::new(I2cLoopback::default());
+        let mut buf = [0; 2];
+        block_on(i2c.write(0, &[1, 2])).unwrap();
+        block_on(i2c.read(0, &mut buf)).unwrap();
+        assert_eq!(buf, [1, 2]);
+        block_on(i2c.write_read(0, &[3, 4], &mut buf)).unwrap();
+        assert_eq!(buf, [3, 4]);
+    }
+}
+
+#[cfg(feature = "serde1")]
+mod serde1 {
+    use std::pin::Pin;
+
+    use assert_unmoved::*;
+
+    #[test]
+    fn serialize() {
+        let mut v = Box::pin(AssertUnmoved::new(vec![1, 2]));
+        let _ = v.as_mut().get_pin_mut();
+        // The tracking state is not serialized.
+        assert_eq!(serde_json::to_string(&*v).unwrap(), "[1,2]");
+    }
+
+    #[test]
+    fn deserialize() {
+        let v: AssertUnmoved<Vec<i32>> = serde_json::from_str("[1,2]").unwrap();
+        assert_eq!(*v, [1, 2]);
+        assert!(!v.is_pinned_accessed());
+        assert!(serde_json::from_str::<AssertUnmoved<Vec<i32>>>("{}").is_err());
+        let mut v = Box::pin(v);
+        let _: Pin<&mut Vec<i32>> = v.as_mut().get_pin_mut();
+    }
+}
+
+#[cfg(feature = "tracing01")]
+mod tracing01 {
+    use std::{
+        fmt,
+        panic::{self, AssertUnwindSafe},
+        pin::Pin,
+        sync::{Arc, Mutex},
+    };
+
+    use assert_unmoved::*;
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Level, Metadata, Subscriber,
+    };
+
+    /// Records the fields of error events.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for Recorder {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0.lock().unwrap().push(format!("{}={:?}", field.name(), value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            *metadata.level() == Level::ERROR
+        }
+        fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+        fn event(&self, event: &Event<'_>) {
+            event.record(&mut self.clone());
+        }
+        fn enter(&self, _: &span::Id) {}
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    #[test]
+    fn event_on_move() {
+        let recorder = Recorder::default();
+        let res = tracing::subscriber::with_default(recorder.clone(), || {
+            panic::catch_unwind(AssertUnwindSafe(|| {
+                let mut x = AssertUnmoved::with_tag((), &"traced");
+                let _ = unsafe { Pin::new_unchecked(&mut x) }.get_pin_mut();
+                let mut x = Box::new(x);
+                let _ = unsafe { Pin::new_unchecked(&mut *x) }.get_pin_mut();
+            }))
+        });
+        assert!(res.is_err());
+
+        let fields = recorder.0.lock().unwrap();
+        let field = |name: &str| {
+            fields.iter().find(|f| f.starts_with(&format!("{}=", name))).unwrap().clone()
+        };
+        assert!(field("message")
+            .starts_with("message=AssertUnmoved[traced] moved between get_pin_mut calls\n"));
+        assert!(field("original_addr").starts_with("original_addr="));
+        assert!(field("current_addr").starts_with("current_addr="));
+        assert!(field("first_pinned_at").contains(file!()));
+        assert!(field("detected_at").contains(file!()));
+        assert_eq!(field("tag"), "tag=\"traced\"");
+    }
+}
+
+#[cfg(feature = "log04")]
+mod log04 {
+    use std::{
+        panic::{self, AssertUnwindSafe},
+        pin::Pin,
+        sync::Mutex,
+    };
+
+    use assert_unmoved::*;
+    use log::{Level, Log, Metadata, Record};
+
+    /// Records the messages of error records.
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl Log for Recorder {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.level() == Level::Error
+        }
+        fn log(&self, record: &Record<'_>) {
+            if self.enabled(record.metadata()) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+        }
+        fn flush(&self) {}
+    }
+
+    static RECORDER: Recorder = Recorder(Mutex::new(Vec::new()));
+
+    #[test]
+    fn log_on_move() {
+        log::set_logger(&RECORDER).unwrap();
+        log::set_max_level(log::LevelFilter::Error);
+
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut x = AssertUnmoved::with_tag(vec![1], &"logged");
+            let _ = unsafe { Pin::new_unchecked(&mut x) }.get_pin_mut();
+            let mut x = Box::new(x);
+            let _ = x.get_mut();
+        }));
+        assert!(res.is_err());
+
+        // Other tests running in parallel may also log, so look for our tag.
+        let records = RECORDER.0.lock().unwrap();
+        let msg = records.iter().find(|r| r.starts_with("AssertUnmoved[logged]")).unwrap();
+        assert!(
+            msg.starts_with(
+                "AssertUnmoved[logged] moved after get_pin_mut call (detected in get_mut)\n"
+            ),
+            "{}",
+            msg
+        );
+        assert!(
+            msg.contains(&format!("\tfirst pinned mutably accessed at {}:", file!())),
+            "{}",
+            msg
+        );
+    }
+}
+
+#[cfg(feature = "testing")]
+mod testing {
+    use std::{
+        future::{pending, ready, Future, Pending},
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use assert_unmoved::{
+        testing::{assert_stable, noop_cx, noop_waker, poll_once},
+        AssertUnmoved,
+    };
+
+    #[test]
+    fn noop() {
+        let waker = noop_waker();
+        waker.wake_by_ref();
+        let cloned = waker.clone();
+        cloned.wake();
+        let mut cx = noop_cx();
+        cx.waker().wake_by_ref();
+        let mut future = Box::pin(AssertUnmoved::new(ready(1)));
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(1));
+        assert_eq!(assert_stable(ready(2), 1), Poll::Ready(2));
+        assert_eq!(assert_stable(pending::<()>(), 2), Poll::Pending);
+    }
+
+    #[test]
+    fn poll_once_() {
+        let mut future = Box::pin(AssertUnmoved::new(ready(1)));
+        assert_eq!(poll_once(future.as_mut()), Poll::Ready(1));
+        let mut future = Box::pin(AssertUnmoved::new(pending::<()>()));
+        assert_eq!(poll_once(future.as_mut()), Poll::Pending);
+        assert_eq!(poll_once(future.as_mut()), Poll::Pending);
+    }
+
+    #[test]
+    #[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+    fn poll_once_moved() {
+        let mut future = AssertUnmoved::new(pending::<()>());
+        let _ = poll_once(unsafe { Pin::new_unchecked(&mut future) });
+        let mut future = Box::new(future);
+        let _ = poll_once(unsafe { Pin::new_unchecked(&mut *future) });
+    }
+
+    #[test]
+    fn assert_pin_stable() {
+        assert_eq!(assert_unmoved::assert_pin_stable!(pending::<()>(), 3), Poll::Pending);
+        assert_eq!(assert_unmoved::assert_pin_stable!(ready(1), 3), Poll::Ready(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+    fn assert_pin_stable_moved() {
+        // A future that incorrectly moves a pinned inner future between polls.
+        struct Mover(Option<AssertUnmoved<Pending<()>>>, Option<Box<AssertUnmoved<Pending<()>>>>);
+
+        impl Future for Mover {
+            type Output = ();
+
+            fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                let this = unsafe { self.get_unchecked_mut() };
+                if let Some(mut inner) = this.0.take() {
+                    assert!(unsafe { Pin::new_unchecked(&mut inner) }.poll(cx).is_pending());
+                    this.1 = Some(Box::new(inner));
+                    Poll::Pending
+                } else {
+                    unsafe { Pin::new_unchecked(&mut **this.1.as_mut().unwrap()) }.poll(cx)
+                }
+            }
+        }
+
+        let _ =
+            assert_unmoved::assert_pin_stable!(Mover(Some(AssertUnmoved::new(pending())), None), 2);
+    }
+
+    #[test]
+    fn pin_and_poll() {
+        let mut cx = noop_cx();
+        let poll = assert_unmoved::pin_and_poll!(pending::<()>(), &mut cx);
+        assert_eq!(poll, Poll::Pending);
+        assert_eq!(assert_unmoved::pin_and_poll!(ready(1), &mut cx), Poll::Ready(1));
+    }
+
+    #[cfg(feature = "futures03")]
+    #[test]
+    fn assert_stream_stable() {
+        use futures::stream;
+
+        assert_eq!(assert_unmoved::assert_stream_stable!(stream::iter(vec![1, 2]), 5), [1, 2]);
+        assert!(assert_unmoved::assert_stream_stable!(stream::pending::<()>(), 5).is_empty());
+    }
+
+    #[cfg(feature = "futures03")]
+    #[test]
+    fn poll_stream_once() {
+        use assert_unmoved::testing::poll_stream_once;
+        use futures::stream;
+
+        let mut stream = Box::pin(AssertUnmoved::new(stream::iter(vec![1, 2])));
+        assert_eq!(poll_stream_once(stream.as_mut()), Poll::Ready(Some(1)));
+        assert_eq!(poll_stream_once(stream.as_mut()), Poll::Ready(Some(2)));
+        assert_eq!(poll_stream_once(stream.as_mut()), Poll::Ready(None));
+    }
+}
+
+#[cfg(feature = "macros")]
+mod macros {
+    use std::{
+        future::{pending, ready, Future, Pending, Ready},
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use assert_unmoved::{assert_pinned_fields, AssertUnmoved};
+    use futures::task::noop_waker;
+    use pin_project::pin_project;
+
+    #[assert_pinned_fields]
+    #[pin_project]
+    struct Select<A, B> {
+        #[pin]
+        a: A,
+        #[pin]
+        b: B,
+        polled: usize,
+    }
+
+    impl<A: Future, B: Future<Output = A::Output>> Future for Select<A, B> {
+        type Output = A::Output;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.project();
+            *this.polled += 1;
+            if let Poll::Ready(v) = this.a.poll(cx) {
+                return Poll::Ready(v);
+            }
+            this.b.poll(cx)
+        }
+    }
+
+    #[assert_pinned_fields]
+    #[pin_project(project = EitherProj)]
+    enum Either<A, B> {
+        Left(#[pin] A),
+        Right(#[pin] B),
+    }
+
+    #[test]
+    fn struct_() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut select =
+            Box::pin(Select { a: pending::<i32>().into(), b: ready(1).into(), polled: 0 });
+        let _: &AssertUnmoved<Pending<i32>> = &select.a;
+        let _: &AssertUnmoved<Ready<i32>> = &select.b;
+        assert_eq!(select.as_mut().poll(&mut cx), Poll::Ready(1));
+        assert_eq!(select.polled, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+    fn struct_moved() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut select = Select { a: pending::<()>().into(), b: pending::<()>().into(), polled: 0 };
+        assert!(unsafe { Pin::new_unchecked(&mut select) }.poll(&mut cx).is_pending());
+        let mut select = Box::new(select);
+        let _ = unsafe { Pin::new_unchecked(&mut *select) }.poll(&mut cx);
+    }
+
+    #[test]
+    fn enum_() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut either = Box::pin(Either::<_, Pending<()>>::Left(ready(()).into()));
+        match either.as_mut().project() {
+            EitherProj::Left(a) => assert_eq!(a.poll(&mut cx), Poll::Ready(())),
+            EitherProj::Right(_) => unreachable!(),
+        }
+        match &*either {
+            Either::Left(a) => assert!(a.is_pinned_accessed()),
+            Either::Right(_) => unreachable!(),
+        }
+
+        let mut either = Box::pin(Either::<Pending<()>, _>::Right(ready(()).into()));
+        match either.as_mut().project() {
+            EitherProj::Left(_) => unreachable!(),
+            EitherProj::Right(b) => assert_eq!(b.poll(&mut cx), Poll::Ready(())),
+        }
+    }
+}
+
+#[cfg(assert_unmoved_unstable)]
+mod unstable {
+    use std::{
+        ops::{Coroutine, CoroutineState},
+        pin::Pin,
+    };
+
+    use assert_unmoved::AssertUnmoved;
+
+    // `yield` syntax is feature-gated even in cfg-disabled code, so write the
+    // coroutine by hand.
+    struct Doubler(u32);
+
+    impl Coroutine<u32> for Doubler {
+        type Yield = u32;
+        type Return = u32;
+
+        fn resume(mut self: Pin<&mut Self>, arg: u32) -> CoroutineState<u32, u32> {
+            self.0 += 1;
+            if self.0 < 3 {
+                CoroutineState::Yielded(arg * 2)
+            } else {
+                CoroutineState::Complete(self.0)
+            }
+        }
+    }
+
+    #[test]
+    fn coroutine() {
+        let mut coroutine = Box::pin(AssertUnmoved::new(Doubler(0)));
+        assert_eq!(coroutine.as_mut().resume(1), CoroutineState::Yielded(2));
+        assert_eq!(coroutine.as_mut().resume(2), CoroutineState::Yielded(4));
+        assert_eq!(coroutine.as_mut().resume(0), CoroutineState::Complete(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+    fn coroutine_moved() {
+        let mut coroutine = AssertUnmoved::new(Doubler(0));
+        let _ = unsafe { Pin::new_unchecked(&mut coroutine) }.resume(1);
+        let mut coroutine = Box::new(coroutine);
+        let _ = unsafe { Pin::new_unchecked(&mut *coroutine) }.resume(1);
+    }
 }
 
 pub mod assert_impl {
@@ -91,6 +2476,37 @@ pub mod assert_impl {
     use crate::*;
 
     assert_impl!(AssertUnmoved<Pin<Box<dyn Future<Output = ()>>>>: Future<Output = ()>);
+    assert_impl!(Pin<Box<AssertUnmoved<dyn Future<Output = ()>>>>: Future<Output = ()>);
+    assert_impl!(Box<AssertUnmoved<dyn Iterator<Item = ()>>>: Iterator<Item = ()>);
+    assert_impl!(AssertUnmoved<std::ops::Range<i32>>: std::ops::RangeBounds<i32>);
+    assert_impl!(AssertUnmoved<()>: Clone);
+    assert_impl!(AssertUnmoved<std::io::Error>: std::fmt::Display);
+    static_assertions::assert_not_impl_any!(AssertUnmoved<()>: std::fmt::Display);
+    assert_impl!(AssertUnmoved<Pending<()>>: AsRef<Pending<()>>);
+    assert_impl!(AssertUnmoved<()>: AsMut<()>);
+    assert_impl!(AssertUnmoved<()>: std::ops::DerefMut);
+    static_assertions::assert_not_impl_any!(AssertUnmoved<std::marker::PhantomPinned>: std::ops::DerefMut);
+    static_assertions::assert_not_impl_any!(AssertUnmoved<std::marker::PhantomPinned>: AsMut<std::marker::PhantomPinned>);
+    assert_impl!(AssertUnmoved<i32>: PartialEq, Eq, PartialOrd, Ord);
+    assert_impl!(AssertUnmoved<std::ops::Range<i32>>: Iterator<Item = i32>, DoubleEndedIterator, ExactSizeIterator, std::iter::FusedIterator);
+    static_assertions::assert_not_impl_any!(AssertUnmoved<std::iter::FromFn<fn() -> Option<()>>>: DoubleEndedIterator, ExactSizeIterator);
+    assert_impl!(AssertUnmoved<f64>: PartialEq, PartialOrd);
+    static_assertions::assert_not_impl_any!(AssertUnmoved<f64>: Eq, Ord);
+    assert_impl!(AssertUnmoved<i32>: std::hash::Hash);
+    static_assertions::assert_not_impl_any!(AssertUnmoved<f64>: std::hash::Hash);
+    assert_impl!(AssertUnmoved<String>: std::fmt::Write);
+    static_assertions::assert_not_impl_any!(AssertUnmoved<()>: std::fmt::Write);
+    #[cfg(feature = "std")]
+    assert_impl!(AssertUnmoved<std::io::Cursor<Vec<u8>>>: std::io::Read, std::io::Write, std::io::Seek, std::io::BufRead);
+    static_assertions::assert_not_impl_any!(AssertUnmoved<std::sync::Mutex<()>>: Clone);
+    assert_impl!(NoCheck<Pin<Box<dyn Future<Output = ()>>>>: Future<Output = ()>);
+    static_assertions::assert_not_impl_any!(NoCheck<()>: Unpin);
+    assert_impl!(Pin<Box<NoCheck<dyn Future<Output = ()>>>>: Future<Output = ()>);
+    assert_impl!(Box<NoCheck<dyn Iterator<Item = ()>>>: Iterator<Item = ()>);
+    assert_impl!(NoCheck<std::ops::Range<i32>>: Iterator<Item = i32>, Clone, PartialEq, Eq, std::hash::Hash);
+    static_assertions::assert_not_impl_any!(NoCheck<std::marker::PhantomPinned>: Unpin);
+    #[cfg(feature = "std")]
+    assert_impl!(NoCheck<std::io::Cursor<Vec<u8>>>: std::io::Read, std::io::Write, std::io::Seek, std::io::BufRead);
 
     #[cfg(feature = "futures03")]
     assert_impl!(AssertUnmoved<Pin<Box<dyn futures_core::FusedFuture<Output = ()>>>>: futures_core::FusedFuture<Output = ()>);
@@ -108,6 +2524,8 @@ pub mod assert_impl {
     assert_impl!(AssertUnmoved<Pin<Box<dyn futures_io::AsyncSeek>>>: futures_io::AsyncSeek);
     #[cfg(feature = "futures03")]
     assert_impl!(AssertUnmoved<Pin<Box<dyn futures_io::AsyncBufRead>>>: futures_io::AsyncBufRead);
+    #[cfg(feature = "futures03")]
+    assert_impl!(NoCheck<Pin<Box<dyn futures_core::Stream<Item = ()>>>>: futures_core::Stream<Item = ()>);
 
     #[cfg(feature = "tokio02")]
     assert_impl!(AssertUnmoved<Pin<Box<dyn tokio02::io::AsyncRead>>>: tokio02::io::AsyncRead);
@@ -135,4 +2553,18 @@ pub mod assert_impl {
     assert_impl!(AssertUnmoved<Pin<Box<dyn tokio1::io::AsyncSeek>>>: tokio1::io::AsyncSeek);
     #[cfg(feature = "tokio1")]
     assert_impl!(AssertUnmoved<Pin<Box<dyn tokio1::io::AsyncBufRead>>>: tokio1::io::AsyncBufRead);
+    #[cfg(feature = "tokio1")]
+    assert_impl!(NoCheck<Pin<Box<dyn tokio1::io::AsyncRead>>>: tokio1::io::AsyncRead);
+    #[cfg(feature = "tower-service03")]
+    assert_impl!(AssertUnmoved<Box<dyn tower_service::Service<(), Response = (), Error = (), Future = std::future::Ready<Result<(), ()>>>>>: tower_service::Service<()>);
+    #[cfg(feature = "http-body1")]
+    assert_impl!(AssertUnmoved<Pin<Box<dyn http_body::Body<Data = bytes1_crate::Bytes, Error = ()>>>>: http_body::Body);
+    #[cfg(feature = "serde1")]
+    assert_impl!(AssertUnmoved<i32>: serde::Serialize, serde::de::DeserializeOwned);
+    #[cfg(feature = "tokio-util07")]
+    assert_impl!(AssertUnmoved<tokio_util07_crate::codec::BytesCodec>: tokio_util07_crate::codec::Encoder<bytes1_crate::Bytes>, tokio_util07_crate::codec::Decoder);
+    #[cfg(feature = "bytes1")]
+    assert_impl!(AssertUnmoved<bytes1_crate::Bytes>: bytes1_crate::Buf);
+    #[cfg(feature = "bytes1")]
+    assert_impl!(AssertUnmoved<Vec<u8>>: bytes1_crate::BufMut);
 }
@@ -0,0 +1,17 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Run with `wasm-pack test --node -- --test wasm`.
+
+#![cfg(target_arch = "wasm32")]
+
+use assert_unmoved::AssertUnmoved;
+use wasm_bindgen_futures::{js_sys::Promise, wasm_bindgen::JsValue, JsFuture};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+async fn js_future() {
+    let promise = Promise::resolve(&JsValue::from(1));
+    let future = Box::pin(AssertUnmoved::new(JsFuture::from(promise)));
+    assert!(!future.is_pinned_accessed());
+    assert_eq!(future.await.unwrap(), 1);
+}
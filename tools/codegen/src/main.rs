@@ -11,6 +11,7 @@ use std::{
 };
 
 use fs_err as fs;
+use proc_macro2::TokenStream;
 use quote::{format_ident, quote, ToTokens as _};
 use syn::visit_mut::{self, VisitMut};
 
@@ -18,12 +19,13 @@ use crate::file::*;
 
 fn main() {
     gen_assert_impl();
+    gen_move_detection_tests();
 }
 
 fn gen_assert_impl() {
     const NOT_SEND: &[&str] = &[];
     const NOT_SYNC: &[&str] = &[];
-    const NOT_UNPIN: &[&str] = &["assert_unmoved::AssertUnmoved"];
+    const NOT_UNPIN: &[&str] = &["assert_unmoved::AssertUnmoved", "no_check::NoCheck"];
     const NOT_UNWIND_SAFE: &[&str] = &[];
     const NOT_REF_UNWIND_SAFE: &[&str] = &[];
 
@@ -299,6 +301,983 @@ fn gen_assert_impl() {
     write(function_name!(), out_dir.join("assert_impl.rs"), out).unwrap();
 }
 
+/// The cfg a trait impl is gated on.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Cfg {
+    /// `cfg(feature = "...")`
+    Feature(String),
+    /// A custom cfg passed via `--cfg`, e.g., `cfg(assert_unmoved_unstable)`.
+    Flag(String),
+}
+
+impl Cfg {
+    fn to_attr(&self) -> TokenStream {
+        match self {
+            Self::Feature(feature) => quote! { #[cfg(feature = #feature)] },
+            Self::Flag(flag) => {
+                let flag = format_ident!("{}", flag);
+                quote! { #[cfg(#flag)] }
+            }
+        }
+    }
+
+    fn test_name_prefix(&self) -> String {
+        match self {
+            Self::Feature(feature) => format!("{}_", feature.replace('-', "_")),
+            Self::Flag(flag) => format!("{flag}_"),
+        }
+    }
+}
+
+struct MoveDetectionTest {
+    cfg: Option<Cfg>,
+    trait_name: &'static str,
+    mock_impl: TokenStream,
+    call: TokenStream,
+}
+
+fn t(
+    feature: Option<&'static str>,
+    trait_name: &'static str,
+    mock_impl: TokenStream,
+    call: TokenStream,
+) -> MoveDetectionTest {
+    let cfg = feature.map(|feature| Cfg::Feature(feature.to_owned()));
+    MoveDetectionTest { cfg, trait_name, mock_impl, call }
+}
+
+/// Tests for traits with pinned methods. `x` is `Pin<&mut AssertUnmoved<Mock>>`
+/// and `cx` is `&mut Context<'_>`.
+fn move_detection_tests() -> Vec<MoveDetectionTest> {
+    vec![
+        t(
+            None,
+            "Future",
+            quote! {
+                impl core::future::Future for Mock {
+                    type Output = ();
+                    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { core::future::Future::poll(x, cx) },
+        ),
+        t(
+            Some("futures-core03"),
+            "Stream",
+            quote! {
+                impl futures_core::Stream for Mock {
+                    type Item = ();
+                    fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<()>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { futures_core::Stream::poll_next(x, cx) },
+        ),
+        t(
+            Some("futures-core03"),
+            "Sink",
+            quote! {
+                impl futures_sink::Sink<()> for Mock {
+                    type Error = ();
+                    fn poll_ready(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<Result<(), ()>> {
+                        Poll::Pending
+                    }
+                    fn start_send(self: Pin<&mut Self>, (): ()) -> Result<(), ()> {
+                        Ok(())
+                    }
+                    fn poll_flush(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<Result<(), ()>> {
+                        Poll::Pending
+                    }
+                    fn poll_close(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<Result<(), ()>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { futures_sink::Sink::<()>::poll_ready(x, cx) },
+        ),
+        t(
+            Some("futures03"),
+            "AsyncRead",
+            quote! {
+                impl futures_io::AsyncRead for Mock {
+                    fn poll_read(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                        _: &mut [u8],
+                    ) -> Poll<futures_io::Result<usize>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { futures_io::AsyncRead::poll_read(x, cx, &mut [0; 1]) },
+        ),
+        t(
+            Some("futures03"),
+            "AsyncWrite",
+            quote! {
+                impl futures_io::AsyncWrite for Mock {
+                    fn poll_write(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                        _: &[u8],
+                    ) -> Poll<futures_io::Result<usize>> {
+                        Poll::Pending
+                    }
+                    fn poll_flush(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<futures_io::Result<()>> {
+                        Poll::Pending
+                    }
+                    fn poll_close(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<futures_io::Result<()>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { futures_io::AsyncWrite::poll_write(x, cx, &[0]) },
+        ),
+        t(
+            Some("futures03"),
+            "AsyncSeek",
+            quote! {
+                impl futures_io::AsyncSeek for Mock {
+                    fn poll_seek(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                        _: futures_io::SeekFrom,
+                    ) -> Poll<futures_io::Result<u64>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { futures_io::AsyncSeek::poll_seek(x, cx, futures_io::SeekFrom::Start(0)) },
+        ),
+        t(
+            Some("futures03"),
+            "AsyncBufRead",
+            quote! {
+                impl futures_io::AsyncBufRead for Mock {
+                    fn poll_fill_buf(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<futures_io::Result<&[u8]>> {
+                        Poll::Pending
+                    }
+                    fn consume(self: Pin<&mut Self>, _: usize) {}
+                }
+            },
+            quote! { futures_io::AsyncBufRead::poll_fill_buf(x, cx) },
+        ),
+        t(
+            Some("tokio02"),
+            "AsyncRead",
+            quote! {
+                impl tokio02_crate::io::AsyncRead for Mock {
+                    fn poll_read(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                        _: &mut [u8],
+                    ) -> Poll<std::io::Result<usize>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { tokio02_crate::io::AsyncRead::poll_read(x, cx, &mut [0; 1]) },
+        ),
+        t(
+            Some("tokio02"),
+            "AsyncWrite",
+            quote! {
+                impl tokio02_crate::io::AsyncWrite for Mock {
+                    fn poll_write(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                        _: &[u8],
+                    ) -> Poll<std::io::Result<usize>> {
+                        Poll::Pending
+                    }
+                    fn poll_flush(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<std::io::Result<()>> {
+                        Poll::Pending
+                    }
+                    fn poll_shutdown(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<std::io::Result<()>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { tokio02_crate::io::AsyncWrite::poll_write(x, cx, &[0]) },
+        ),
+        t(
+            Some("tokio02"),
+            "AsyncSeek",
+            quote! {
+                impl tokio02_crate::io::AsyncSeek for Mock {
+                    fn start_seek(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                        _: std::io::SeekFrom,
+                    ) -> Poll<std::io::Result<()>> {
+                        Poll::Pending
+                    }
+                    fn poll_complete(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<std::io::Result<u64>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { tokio02_crate::io::AsyncSeek::start_seek(x, cx, std::io::SeekFrom::Start(0)) },
+        ),
+        t(
+            Some("tokio02"),
+            "AsyncBufRead",
+            quote! {
+                impl tokio02_crate::io::AsyncBufRead for Mock {
+                    fn poll_fill_buf(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<std::io::Result<&[u8]>> {
+                        Poll::Pending
+                    }
+                    fn consume(self: Pin<&mut Self>, _: usize) {}
+                }
+            },
+            quote! { tokio02_crate::io::AsyncBufRead::poll_fill_buf(x, cx) },
+        ),
+        t(
+            Some("tokio03"),
+            "AsyncRead",
+            quote! {
+                impl tokio03_crate::io::AsyncRead for Mock {
+                    fn poll_read(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                        _: &mut tokio03_crate::io::ReadBuf<'_>,
+                    ) -> Poll<std::io::Result<()>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! {
+                tokio03_crate::io::AsyncRead::poll_read(
+                    x,
+                    cx,
+                    &mut tokio03_crate::io::ReadBuf::new(&mut [0; 1]),
+                )
+            },
+        ),
+        t(
+            Some("tokio03"),
+            "AsyncWrite",
+            quote! {
+                impl tokio03_crate::io::AsyncWrite for Mock {
+                    fn poll_write(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                        _: &[u8],
+                    ) -> Poll<std::io::Result<usize>> {
+                        Poll::Pending
+                    }
+                    fn poll_flush(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<std::io::Result<()>> {
+                        Poll::Pending
+                    }
+                    fn poll_shutdown(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<std::io::Result<()>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { tokio03_crate::io::AsyncWrite::poll_write(x, cx, &[0]) },
+        ),
+        t(
+            Some("tokio03"),
+            "AsyncSeek",
+            quote! {
+                impl tokio03_crate::io::AsyncSeek for Mock {
+                    fn start_seek(
+                        self: Pin<&mut Self>,
+                        _: std::io::SeekFrom,
+                    ) -> std::io::Result<()> {
+                        Ok(())
+                    }
+                    fn poll_complete(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<std::io::Result<u64>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { tokio03_crate::io::AsyncSeek::poll_complete(x, cx) },
+        ),
+        t(
+            Some("tokio03"),
+            "AsyncBufRead",
+            quote! {
+                impl tokio03_crate::io::AsyncBufRead for Mock {
+                    fn poll_fill_buf(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<std::io::Result<&[u8]>> {
+                        Poll::Pending
+                    }
+                    fn consume(self: Pin<&mut Self>, _: usize) {}
+                }
+            },
+            quote! { tokio03_crate::io::AsyncBufRead::poll_fill_buf(x, cx) },
+        ),
+        t(
+            Some("tokio1"),
+            "AsyncRead",
+            quote! {
+                impl tokio1_crate::io::AsyncRead for Mock {
+                    fn poll_read(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                        _: &mut tokio1_crate::io::ReadBuf<'_>,
+                    ) -> Poll<std::io::Result<()>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! {
+                tokio1_crate::io::AsyncRead::poll_read(
+                    x,
+                    cx,
+                    &mut tokio1_crate::io::ReadBuf::new(&mut [0; 1]),
+                )
+            },
+        ),
+        t(
+            Some("tokio1"),
+            "AsyncWrite",
+            quote! {
+                impl tokio1_crate::io::AsyncWrite for Mock {
+                    fn poll_write(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                        _: &[u8],
+                    ) -> Poll<std::io::Result<usize>> {
+                        Poll::Pending
+                    }
+                    fn poll_flush(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<std::io::Result<()>> {
+                        Poll::Pending
+                    }
+                    fn poll_shutdown(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<std::io::Result<()>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { tokio1_crate::io::AsyncWrite::poll_write(x, cx, &[0]) },
+        ),
+        t(
+            Some("tokio1"),
+            "AsyncSeek",
+            quote! {
+                impl tokio1_crate::io::AsyncSeek for Mock {
+                    fn start_seek(
+                        self: Pin<&mut Self>,
+                        _: std::io::SeekFrom,
+                    ) -> std::io::Result<()> {
+                        Ok(())
+                    }
+                    fn poll_complete(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<std::io::Result<u64>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { tokio1_crate::io::AsyncSeek::poll_complete(x, cx) },
+        ),
+        t(
+            Some("tokio1"),
+            "AsyncBufRead",
+            quote! {
+                impl tokio1_crate::io::AsyncBufRead for Mock {
+                    fn poll_fill_buf(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<std::io::Result<&[u8]>> {
+                        Poll::Pending
+                    }
+                    fn consume(self: Pin<&mut Self>, _: usize) {}
+                }
+            },
+            quote! { tokio1_crate::io::AsyncBufRead::poll_fill_buf(x, cx) },
+        ),
+        t(
+            Some("http-body1"),
+            "Body",
+            quote! {
+                impl http_body::Body for Mock {
+                    type Data = &'static [u8];
+                    type Error = ();
+                    fn poll_frame(
+                        self: Pin<&mut Self>,
+                        _: &mut Context<'_>,
+                    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, ()>>> {
+                        Poll::Pending
+                    }
+                }
+            },
+            quote! { http_body::Body::poll_frame(x, cx) },
+        ),
+        MoveDetectionTest {
+            cfg: Some(Cfg::Flag("assert_unmoved_unstable".to_owned())),
+            trait_name: "Coroutine",
+            mock_impl: quote! {
+                impl core::ops::Coroutine for Mock {
+                    type Yield = ();
+                    type Return = ();
+                    fn resume(
+                        self: Pin<&mut Self>,
+                        (): (),
+                    ) -> core::ops::CoroutineState<(), ()> {
+                        core::ops::CoroutineState::Yielded(())
+                    }
+                }
+            },
+            call: quote! {{
+                let _ = cx;
+                core::ops::Coroutine::resume(x, ())
+            }},
+        },
+    ]
+}
+
+/// Tests for traits with `&mut self` methods, which go through `get_mut`. `x`
+/// is `&mut AssertUnmoved<Mock>`.
+fn unpinned_move_detection_tests() -> Vec<MoveDetectionTest> {
+    vec![
+        t(None, "DerefMut", quote! {}, quote! { &mut **x }),
+        t(None, "AsMut", quote! {}, quote! { AsMut::<Mock>::as_mut(x) }),
+        t(
+            None,
+            "Iterator",
+            quote! {
+                impl Iterator for Mock {
+                    type Item = ();
+                    fn next(&mut self) -> Option<()> {
+                        None
+                    }
+                }
+            },
+            quote! { Iterator::next(x) },
+        ),
+        t(
+            None,
+            "DoubleEndedIterator",
+            quote! {
+                impl DoubleEndedIterator for Mock {
+                    fn next_back(&mut self) -> Option<()> {
+                        None
+                    }
+                }
+            },
+            quote! { DoubleEndedIterator::next_back(x) },
+        ),
+        t(
+            None,
+            "Write",
+            quote! {
+                impl core::fmt::Write for Mock {
+                    fn write_str(&mut self, _: &str) -> core::fmt::Result {
+                        Ok(())
+                    }
+                }
+            },
+            quote! { core::fmt::Write::write_str(x, "") },
+        ),
+        t(
+            Some("std"),
+            "Read",
+            quote! {
+                impl std::io::Read for Mock {
+                    fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                        Ok(0)
+                    }
+                }
+            },
+            quote! { std::io::Read::read(x, &mut [0; 1]) },
+        ),
+        t(
+            Some("std"),
+            "Write",
+            quote! {
+                impl std::io::Write for Mock {
+                    fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                        Ok(0)
+                    }
+                    fn flush(&mut self) -> std::io::Result<()> {
+                        Ok(())
+                    }
+                }
+            },
+            quote! { std::io::Write::write(x, &[0]) },
+        ),
+        t(
+            Some("std"),
+            "Seek",
+            quote! {
+                impl std::io::Seek for Mock {
+                    fn seek(&mut self, _: std::io::SeekFrom) -> std::io::Result<u64> {
+                        Ok(0)
+                    }
+                }
+            },
+            quote! { std::io::Seek::seek(x, std::io::SeekFrom::Start(0)) },
+        ),
+        t(
+            Some("std"),
+            "BufRead",
+            quote! {
+                impl std::io::BufRead for Mock {
+                    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+                        Ok(&[])
+                    }
+                    fn consume(&mut self, _: usize) {}
+                }
+            },
+            quote! { std::io::BufRead::fill_buf(x) },
+        ),
+        t(
+            Some("bytes1"),
+            "Buf",
+            quote! {
+                impl bytes1_crate::Buf for Mock {
+                    fn remaining(&self) -> usize {
+                        0
+                    }
+                    fn chunk(&self) -> &[u8] {
+                        &[]
+                    }
+                    fn advance(&mut self, _: usize) {}
+                }
+            },
+            quote! { bytes1_crate::Buf::copy_to_bytes(x, 0) },
+        ),
+        t(
+            Some("bytes1"),
+            "BufMut",
+            quote! {
+                unsafe impl bytes1_crate::BufMut for Mock {
+                    fn remaining_mut(&self) -> usize {
+                        0
+                    }
+                    unsafe fn advance_mut(&mut self, _: usize) {}
+                    fn chunk_mut(&mut self) -> &mut bytes1_crate::buf::UninitSlice {
+                        bytes1_crate::buf::UninitSlice::new(&mut [])
+                    }
+                }
+            },
+            quote! { bytes1_crate::BufMut::chunk_mut(x) },
+        ),
+        t(
+            Some("tokio-util07"),
+            "Decoder",
+            quote! {
+                impl tokio_util07_crate::codec::Decoder for Mock {
+                    type Item = ();
+                    type Error = std::io::Error;
+                    fn decode(
+                        &mut self,
+                        _: &mut bytes1_crate::BytesMut,
+                    ) -> std::io::Result<Option<()>> {
+                        Ok(None)
+                    }
+                }
+            },
+            quote! {
+                tokio_util07_crate::codec::Decoder::decode(x, &mut bytes1_crate::BytesMut::new())
+            },
+        ),
+        t(
+            Some("tokio-util07"),
+            "Encoder",
+            quote! {
+                impl tokio_util07_crate::codec::Encoder<()> for Mock {
+                    type Error = std::io::Error;
+                    fn encode(
+                        &mut self,
+                        (): (),
+                        _: &mut bytes1_crate::BytesMut,
+                    ) -> std::io::Result<()> {
+                        Ok(())
+                    }
+                }
+            },
+            quote! {
+                tokio_util07_crate::codec::Encoder::encode(
+                    x,
+                    (),
+                    &mut bytes1_crate::BytesMut::new(),
+                )
+            },
+        ),
+        t(
+            Some("tower-service03"),
+            "Service",
+            quote! {
+                impl tower_service::Service<()> for Mock {
+                    type Response = ();
+                    type Error = ();
+                    type Future = core::future::Ready<Result<(), ()>>;
+                    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+                        Poll::Ready(Ok(()))
+                    }
+                    fn call(&mut self, (): ()) -> Self::Future {
+                        core::future::ready(Ok(()))
+                    }
+                }
+            },
+            quote! { tower_service::Service::call(x, ()) },
+        ),
+        t(
+            Some("embedded-hal-async1"),
+            "SpiBus",
+            quote! {
+                impl embedded_hal_async::spi::ErrorType for Mock {
+                    type Error = core::convert::Infallible;
+                }
+                impl embedded_hal_async::spi::SpiBus for Mock {
+                    async fn read(&mut self, _: &mut [u8]) -> Result<(), Self::Error> {
+                        Ok(())
+                    }
+                    async fn write(&mut self, _: &[u8]) -> Result<(), Self::Error> {
+                        Ok(())
+                    }
+                    async fn transfer(
+                        &mut self,
+                        _: &mut [u8],
+                        _: &[u8],
+                    ) -> Result<(), Self::Error> {
+                        Ok(())
+                    }
+                    async fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Self::Error> {
+                        Ok(())
+                    }
+                    async fn flush(&mut self) -> Result<(), Self::Error> {
+                        Ok(())
+                    }
+                }
+            },
+            quote! { embedded_hal_async::spi::SpiBus::write(x, &[0]) },
+        ),
+        t(
+            Some("embedded-hal-async1"),
+            "I2c",
+            quote! {
+                impl embedded_hal_async::i2c::ErrorType for Mock {
+                    type Error = core::convert::Infallible;
+                }
+                impl embedded_hal_async::i2c::I2c for Mock {
+                    async fn transaction(
+                        &mut self,
+                        _: u8,
+                        _: &mut [embedded_hal_async::i2c::Operation<'_>],
+                    ) -> Result<(), Self::Error> {
+                        Ok(())
+                    }
+                }
+            },
+            quote! { embedded_hal_async::i2c::I2c::write(x, 0, &[0]) },
+        ),
+    ]
+}
+
+fn gen_move_detection_tests() {
+    let workspace_root = &workspace_root();
+    let out_dir = &workspace_root.join("src/gen/tests");
+    fs::create_dir_all(out_dir).unwrap();
+
+    let files: BTreeSet<String> = test_helper::git::ls_files(workspace_root.join("src"), &["*.rs"])
+        .into_iter()
+        .filter_map(|(file_name, path)| {
+            if file_name.starts_with("gen/") {
+                return None;
+            }
+            Some(path.to_string_lossy().into_owned())
+        })
+        .collect();
+
+    // Collect traits implemented for AssertUnmoved that have pinned mutable
+    // methods, and those that only have `&mut self` methods.
+    let mut impls = BTreeSet::new();
+    let mut unpinned_impls = BTreeSet::new();
+    for f in &files {
+        // Trait impls shared with NoCheck are written against `$ty` in the
+        // `forward_impls!` macro.
+        let s = fs::read_to_string(f).unwrap().replace("$ty", "AssertUnmoved");
+        let ast = syn::parse_file(&s).unwrap();
+        collect_trait_impls(&ast.items, None, &mut impls, &mut unpinned_impls);
+    }
+
+    let mut tokens = quote! {};
+    let mut used = BTreeSet::new();
+    for test in move_detection_tests() {
+        let key = (test.cfg.clone(), test.trait_name.to_owned());
+        assert!(
+            impls.contains(&key),
+            "unknown trait `{}` (cfg: {:?}) specified in move_detection_tests",
+            test.trait_name,
+            test.cfg,
+        );
+        used.insert(key);
+
+        let MoveDetectionTest { cfg, trait_name, mock_impl, call } = test;
+        let test_name = format_ident!(
+            "{}{}",
+            cfg.as_ref().map(Cfg::test_name_prefix).unwrap_or_default(),
+            to_snake_case(trait_name)
+        );
+        let cfg = cfg.as_ref().map(Cfg::to_attr);
+        // The mock may consist of multiple impls, each of which needs the cfg.
+        let mock_impl = syn::parse2::<syn::File>(mock_impl).unwrap().items;
+        let mock_impl = mock_impl.iter().map(|item| quote! { #cfg #item });
+        tokens.extend(quote! {
+            #(#mock_impl)*
+            #cfg
+            #[test]
+            #[should_panic(expected = "AssertUnmoved moved between get_pin_mut calls")]
+            fn #test_name() {
+                assert_move_detected(Mock, |x, cx| {
+                    let _ = #call;
+                });
+            }
+        });
+    }
+    for (cfg, trait_name) in &impls {
+        assert!(
+            used.contains(&(cfg.clone(), trait_name.clone())),
+            "move detection test for `{}` (cfg: {:?}) is missing; \
+             add it to move_detection_tests",
+            trait_name,
+            cfg,
+        );
+    }
+    for test in unpinned_move_detection_tests() {
+        let key = (test.cfg.clone(), test.trait_name.to_owned());
+        assert!(
+            unpinned_impls.contains(&key),
+            "unknown trait `{}` (cfg: {:?}) specified in unpinned_move_detection_tests",
+            test.trait_name,
+            test.cfg,
+        );
+        used.insert(key);
+
+        let MoveDetectionTest { cfg, trait_name, mock_impl, call } = test;
+        let test_name = format!(
+            "{}{}",
+            cfg.as_ref().map(Cfg::test_name_prefix).unwrap_or_default(),
+            to_snake_case(trait_name)
+        );
+        let not_pinned_test_name = format_ident!("{}_not_pinned", test_name);
+        let test_name = format_ident!("{}", test_name);
+        let cfg = cfg.as_ref().map(Cfg::to_attr);
+        // The mock may consist of multiple impls, each of which needs the cfg.
+        let mock_impl = syn::parse2::<syn::File>(mock_impl).unwrap().items;
+        let mock_impl = mock_impl.iter().map(|item| quote! { #cfg #item });
+        tokens.extend(quote! {
+            #(#mock_impl)*
+            #cfg
+            #[test]
+            fn #not_pinned_test_name() {
+                assert_move_allowed_before_pin(Mock, |x| {
+                    let _ = #call;
+                });
+            }
+            #cfg
+            #[test]
+            #[should_panic(expected = "AssertUnmoved moved after get_pin_mut call")]
+            fn #test_name() {
+                assert_move_detected_after_pin(Mock, |x| {
+                    let _ = #call;
+                });
+            }
+        });
+    }
+    for (cfg, trait_name) in &unpinned_impls {
+        assert!(
+            used.contains(&(cfg.clone(), trait_name.clone())),
+            "move detection test for `{}` (cfg: {:?}) is missing; \
+             add it to unpinned_move_detection_tests",
+            trait_name,
+            cfg,
+        );
+    }
+
+    let out = quote! {
+        #![allow(
+            clippy::let_underscore_future, // `Mock` and `&mut Mock` implement `Future`
+            clippy::std_instead_of_alloc,
+            clippy::std_instead_of_core,
+            clippy::undocumented_unsafe_blocks,
+        )]
+        use core::{
+            pin::Pin,
+            task::{Context, Poll},
+        };
+        use std::boxed::Box;
+        use crate::assert_unmoved::AssertUnmoved;
+        struct Mock;
+        /// Calls `f` with `AssertUnmoved` pinned on the stack, moves it to the heap,
+        /// and then calls `f` again.
+        fn assert_move_detected<T>(
+            inner: T,
+            mut f: impl FnMut(Pin<&mut AssertUnmoved<T>>, &mut Context<'_>),
+        ) {
+            let waker = futures::task::noop_waker();
+            let mut cx = Context::from_waker(&waker);
+            let mut x = AssertUnmoved::new(inner);
+            f(unsafe { Pin::new_unchecked(&mut x) }, &mut cx);
+            let mut x = Box::new(x);
+            f(unsafe { Pin::new_unchecked(&mut *x) }, &mut cx);
+        }
+        /// Calls `f`, moves `AssertUnmoved` to the heap, and then calls `f` again.
+        /// `AssertUnmoved` is not pinned, so moving it is fine.
+        fn assert_move_allowed_before_pin<T>(inner: T, mut f: impl FnMut(&mut AssertUnmoved<T>)) {
+            let mut x = AssertUnmoved::new(inner);
+            f(&mut x);
+            let mut x = Box::new(x);
+            f(&mut x);
+        }
+        /// Pins `AssertUnmoved` on the stack and accesses it, moves it to the heap,
+        /// and then calls `f`.
+        fn assert_move_detected_after_pin<T>(inner: T, f: impl FnOnce(&mut AssertUnmoved<T>)) {
+            let mut x = AssertUnmoved::new(inner);
+            let _ = unsafe { Pin::new_unchecked(&mut x) }.get_pin_mut();
+            let mut x = Box::new(x);
+            f(&mut x);
+        }
+        #tokens
+    };
+    write(function_name!(), out_dir.join("move_detection.rs"), out).unwrap();
+}
+
+/// Collects traits implemented for `AssertUnmoved` that have pinned mutable
+/// methods into `impls`, and those that only have `&mut self` methods into
+/// `unpinned_impls`.
+fn collect_trait_impls(
+    items: &[syn::Item],
+    cfg: Option<&Cfg>,
+    impls: &mut BTreeSet<(Option<Cfg>, String)>,
+    unpinned_impls: &mut BTreeSet<(Option<Cfg>, String)>,
+) {
+    for item in items {
+        match item {
+            syn::Item::Mod(item) => {
+                let mod_cfg =
+                    item.attrs.iter().filter(|attr| attr.path().is_ident("cfg")).find_map(|attr| {
+                        match attr.parse_args().ok()? {
+                            syn::Meta::Path(path) => Some(Cfg::Flag(path.get_ident()?.to_string())),
+                            syn::Meta::NameValue(syn::MetaNameValue {
+                                path,
+                                value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }),
+                                ..
+                            }) if path.is_ident("feature") => Some(Cfg::Feature(s.value())),
+                            _ => None,
+                        }
+                    });
+                if let Some((_, items)) = &item.content {
+                    collect_trait_impls(items, mod_cfg.as_ref().or(cfg), impls, unpinned_impls);
+                }
+            }
+            syn::Item::Impl(item) => {
+                let Some((_, trait_path, _)) = &item.trait_ else { continue };
+                let is_assert_unmoved = match &*item.self_ty {
+                    syn::Type::Path(ty) => {
+                        ty.path.segments.last().is_some_and(|s| s.ident == "AssertUnmoved")
+                    }
+                    _ => false,
+                };
+                let has_pinned_method = item.items.iter().any(|item| match item {
+                    syn::ImplItem::Fn(f) => f.sig.receiver().is_some_and(|r| {
+                        r.colon_token.is_some()
+                            && r.ty.to_token_stream().to_string().replace(' ', "")
+                                == "Pin<&mutSelf>"
+                    }),
+                    _ => false,
+                });
+                let has_mut_method = item.items.iter().any(|item| match item {
+                    syn::ImplItem::Fn(f) => f.sig.receiver().is_some_and(|r| {
+                        r.colon_token.is_none() && r.reference.is_some() && r.mutability.is_some()
+                    }),
+                    _ => false,
+                });
+                if is_assert_unmoved {
+                    let trait_name = trait_path.segments.last().unwrap().ident.to_string();
+                    if has_pinned_method {
+                        impls.insert((cfg.cloned(), trait_name));
+                    } else if has_mut_method {
+                        unpinned_impls.insert((cfg.cloned(), trait_name));
+                    }
+                }
+            }
+            syn::Item::Macro(item) if item.mac.path.is_ident("macro_rules") => {
+                for tt in item.mac.tokens.clone() {
+                    let proc_macro2::TokenTree::Group(group) = tt else { continue };
+                    if group.delimiter() != proc_macro2::Delimiter::Brace {
+                        continue;
+                    }
+                    if let Ok(file) = syn::parse2::<syn::File>(group.stream()) {
+                        collect_trait_impls(&file.items, cfg, impls, unpinned_impls);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn to_snake_case(s: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in s.char_indices() {
+        if c.is_ascii_uppercase() {
+            if i != 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
 #[must_use]
 struct ItemVisitor<F> {
     module: Vec<syn::PathSegment>,